@@ -0,0 +1,52 @@
+package golden
+
+import (
+    "context"
+    "encoding/json"
+    "errors"
+    "fmt"
+    "time"
+)
+
+// Not every schema needs every import
+var _ = context.Background
+var _ = json.Marshal
+var _ = errors.New
+var _ = fmt.Sprint
+var _ = time.Now
+
+type Level int32
+
+const (
+    LevelLow Level = 0
+    LevelHigh Level = 1
+)
+
+func (v Level) String() string {
+    switch v {
+    case LevelLow:
+        return "Low"
+    case LevelHigh:
+        return "High"
+    }
+    return fmt.Sprint("Level(", int32(v), ")")
+}
+
+func (v Level) MarshalJSON() ([]byte, error) {
+    return json.Marshal(int32(v))
+}
+
+func (v *Level) UnmarshalJSON(data []byte) error {
+    var raw int32
+    if err := json.Unmarshal(data, &raw); err != nil {
+        return err
+    }
+    switch Level(raw) {
+    case LevelLow, LevelHigh:
+        *v = Level(raw)
+        return nil
+    }
+    return errors.New(fmt.Sprint("invalid Level value ", raw))
+}
+
+type Limits = map[string]int32
\ No newline at end of file
@@ -0,0 +1,66 @@
+#![allow(non_snake_case)]
+#![allow(unused_imports)]
+#![allow(deprecated)]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+macro_rules! string_int {
+    ($name:ident, $int:ty) => {
+        /// A 64 bit integer, sent as a string since JSON numbers can not hold every value
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        pub struct $name(pub $int);
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let text = String::deserialize(deserializer)?;
+                text.parse().map($name).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+string_int!(Int64, i64);
+string_int!(Uint64, u64);
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Level {
+    Low = 0,
+    High = 1,
+}
+
+pub type Limits = HashMap<String, i32>;
+
+/// Largest number of items a page can hold
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+pub const MIN_BALANCE: i64 = -500;
+
+pub const RATIO: f32 = -0.25;
+
+pub const SCALE: f64 = 0.0015;
+
+pub const STRICT: bool = true;
+
+pub const TEMP_DIR: &str = "C:\\temp";
+
+pub const DEFAULT_LEVEL: Level = Level::High;
+
+pub const LEVELS: &[Level] = &[Level::Low, Level::High];
+
+pub const LIMITS: &[(&str, i32)] = &[("pages", 10), ("per-page", 50)];
+
+pub const FALLBACK: Option<&str> = None;
+
+pub const ORIGIN: (i32, f64, Option<&str>) = (0, -1.5, None,);
+
+pub const GRID: &[&[u8]] = &[&[0, 1], &[255]];
\ No newline at end of file
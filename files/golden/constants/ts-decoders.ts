@@ -0,0 +1,161 @@
+type Optional<T> = T | null;
+
+export type DecodePath = (string | number)[];
+
+export interface DecodeError extends Error {
+  name: "DecodeError";
+  path: DecodePath;
+}
+
+export function isDecodeError(error: unknown): error is DecodeError {
+  return error instanceof Error && error.name === "DecodeError";
+}
+
+export type Decoder<T> = (value: unknown, path: DecodePath) => T;
+
+function fail(path: DecodePath, expected: string, value: unknown): never {
+  const at = path.map((key) => (typeof key === "number" ? `[${key}]` : `.${key}`)).join("");
+  const error = new Error(`$${at}: expected ${expected}, found ${JSON.stringify(value)}`) as DecodeError;
+  error.name = "DecodeError";
+  error.path = path;
+  throw error;
+}
+
+function decodeString(value: unknown, path: DecodePath): string {
+  return typeof value === "string" ? value : fail(path, "a string", value);
+}
+
+function decodeInt(value: unknown, path: DecodePath): number {
+  return Number.isInteger(value) ? (value as number) : fail(path, "an integer", value);
+}
+
+function decodeIntRange(min: number, max: number): Decoder<number> {
+  return (value, path) => {
+    const int = decodeInt(value, path);
+    return int >= min && int <= max ? int : fail(path, `an integer between ${min} and ${max}`, value);
+  };
+}
+
+// 64 bit integers are sent as strings, since a number can not hold every value
+function decodeInt64(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^-?[0-9]+$/.test(value) ? value : fail(path, "an integer string", value);
+}
+
+function decodeUint64(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^[0-9]+$/.test(value) ? value : fail(path, "an unsigned integer string", value);
+}
+
+function decodeBytes(value: unknown, path: DecodePath): string {
+  const base64 = /^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$/;
+  return typeof value === "string" && base64.test(value) ? value : fail(path, "a base64 string", value);
+}
+
+function decodeTimestamp(value: unknown, path: DecodePath): Date {
+  const rfc3339 = /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$/;
+  const date = typeof value === "string" && rfc3339.test(value) ? new Date(value) : undefined;
+  return date !== undefined && !Number.isNaN(date.getTime()) ? date : fail(path, "an RFC 3339 timestamp", value);
+}
+
+function decodeDate(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^\d{4}-\d{2}-\d{2}$/.test(value) ? value : fail(path, "a date", value);
+}
+
+function decodeUuid(value: unknown, path: DecodePath): string {
+  const uuid = /^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$/;
+  return typeof value === "string" && uuid.test(value) ? value : fail(path, "a UUID", value);
+}
+
+function decodeDecimal(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^-?[0-9]+(\.[0-9]+)?$/.test(value) ? value : fail(path, "a decimal string", value);
+}
+
+function decodeFloat(value: unknown, path: DecodePath): number {
+  return typeof value === "number" ? value : fail(path, "a number", value);
+}
+
+function decodeBool(value: unknown, path: DecodePath): boolean {
+  return typeof value === "boolean" ? value : fail(path, "a boolean", value);
+}
+
+function decodeObject(value: unknown, path: DecodePath): Record<string, unknown> {
+  if (typeof value !== "object" || value === null || Array.isArray(value)) {
+    return fail(path, "an object", value);
+  }
+  return value as Record<string, unknown>;
+}
+
+function decodeNullable<T>(inner: Decoder<T>): Decoder<Optional<T>> {
+  return (value, path) => (value === null ? null : inner(value, path));
+}
+
+function decodeArray<T>(inner: Decoder<T>): Decoder<Array<T>> {
+  return (value, path) => {
+    if (!Array.isArray(value)) {
+      return fail(path, "an array", value);
+    }
+    return value.map((item, i) => inner(item, [...path, i]));
+  };
+}
+
+function decodeMap<T>(inner: Decoder<T>): Decoder<Record<string, T>> {
+  return (value, path) => {
+    const obj = decodeObject(value, path);
+    const result: Record<string, T> = {};
+    for (const key of Object.keys(obj)) {
+      result[key] = inner(obj[key], [...path, key]);
+    }
+    return result;
+  };
+}
+
+function decodeTuple<T extends unknown[]>(...items: { [K in keyof T]: Decoder<T[K]> }): Decoder<T> {
+  return (value, path) => {
+    if (!Array.isArray(value) || value.length !== items.length) {
+      return fail(path, `an array of ${items.length} items`, value);
+    }
+    return (items as Decoder<unknown>[]).map((item, i) => item(value[i], [...path, i])) as T;
+  };
+}
+
+export enum Level {
+  Low = 0,
+  High = 1
+}
+
+export function decodeLevel(value: unknown, path: DecodePath = []): Level {
+  const members: unknown[] = [Level.Low, Level.High];
+  return members.includes(value) ? (value as Level) : fail(path, "a member of Level", value);
+}
+
+export type Limits = Record<string, number>;
+
+export function decodeLimits(value: unknown, path: DecodePath = []): Limits {
+  return decodeMap(decodeIntRange(-2147483648, 2147483647))(value, path);
+}
+
+/**
+ * Largest number of items a page can hold
+ */
+export const MAX_PAGE_SIZE: number = 100;
+
+export const MIN_BALANCE: string = "-500";
+
+export const RATIO: number = -0.25;
+
+export const SCALE: number = 0.0015;
+
+export const STRICT: boolean = true;
+
+export const TEMP_DIR: string = "C:\\temp";
+
+export const DEFAULT_LEVEL: Level = Level.High;
+
+export const LEVELS: Array<Level> = [Level.Low, Level.High];
+
+export const LIMITS: Limits = {pages: 10, "per-page": 50};
+
+export const FALLBACK: Optional<string> = null;
+
+export const ORIGIN: [number, number, Optional<string>] = [0, -1.5, null];
+
+export const GRID: Array<Array<number>> = [[0, 1], [255]];
\ No newline at end of file
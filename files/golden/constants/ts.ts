@@ -0,0 +1,35 @@
+type Optional<T> = T | null;
+
+export enum Level {
+  Low = 0,
+  High = 1
+}
+
+export type Limits = Record<string, number>;
+
+/**
+ * Largest number of items a page can hold
+ */
+export const MAX_PAGE_SIZE: number = 100;
+
+export const MIN_BALANCE: string = "-500";
+
+export const RATIO: number = -0.25;
+
+export const SCALE: number = 0.0015;
+
+export const STRICT: boolean = true;
+
+export const TEMP_DIR: string = "C:\\temp";
+
+export const DEFAULT_LEVEL: Level = Level.High;
+
+export const LEVELS: Array<Level> = [Level.Low, Level.High];
+
+export const LIMITS: Limits = {pages: 10, "per-page": 50};
+
+export const FALLBACK: Optional<string> = null;
+
+export const ORIGIN: [number, number, Optional<string>] = [0, -1.5, null];
+
+export const GRID: Array<Array<number>> = [[0, 1], [255]];
\ No newline at end of file
@@ -0,0 +1,111 @@
+package golden
+
+import (
+    "context"
+    "encoding/json"
+    "errors"
+    "fmt"
+    "time"
+)
+
+// Not every schema needs every import
+var _ = context.Background
+var _ = json.Marshal
+var _ = errors.New
+var _ = fmt.Sprint
+var _ = time.Now
+
+// Fields shared by every stored record
+type Entity struct {
+    Id string `json:"id"`
+    Version int32 `json:"version"`
+}
+
+type plainEntity Entity
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Entity) UnmarshalJSON(data []byte) error {
+    decoded := plainEntity{
+        Version: 1,
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Entity(decoded)
+    return nil
+}
+
+// Inherits the fields of `Entity` before its own
+type Document struct {
+    Id string `json:"id"`
+    Version int32 `json:"version"`
+    Title string `json:"title"`
+    Summary *string `json:"summary,omitempty"`
+}
+
+type plainDocument Document
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Document) UnmarshalJSON(data []byte) error {
+    decoded := plainDocument{
+        Version: 1,
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Document(decoded)
+    return nil
+}
+
+type Report struct {
+    Id string `json:"id"`
+    Version int32 `json:"version"`
+    Title string `json:"title"`
+    Summary *string `json:"summary,omitempty"`
+    Pages []string `json:"pages"`
+}
+
+type plainReport Report
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Report) UnmarshalJSON(data []byte) error {
+    decoded := plainReport{
+        Version: 1,
+        Pages: []string{},
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Report(decoded)
+    return nil
+}
+
+type Labeled[T any] struct {
+    Label string `json:"label"`
+    Value T `json:"value"`
+}
+
+type Score struct {
+    Label string `json:"label"`
+    Value float64 `json:"value"`
+    Max *float64 `json:"max"`
+}
+
+type Marker struct {
+    Id string `json:"id"`
+    Version int32 `json:"version"`
+}
+
+type plainMarker Marker
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Marker) UnmarshalJSON(data []byte) error {
+    decoded := plainMarker{
+        Version: 1,
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Marker(decoded)
+    return nil
+}
\ No newline at end of file
@@ -0,0 +1,87 @@
+#![allow(non_snake_case)]
+#![allow(unused_imports)]
+#![allow(deprecated)]
+
+use serde::{Deserialize, Serialize};
+
+/// Fields shared by every stored record
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entity {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "version", default = "default_Entity_version")]
+    pub version: i32,
+}
+
+fn default_Entity_version() -> i32 {
+    1
+}
+
+/// Inherits the fields of `Entity` before its own
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Document {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "version", default = "default_Document_version")]
+    pub version: i32,
+    #[serde(rename = "title")]
+    pub title: String,
+    #[serde(rename = "summary", skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+fn default_Document_version() -> i32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Report {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "version", default = "default_Report_version")]
+    pub version: i32,
+    #[serde(rename = "title")]
+    pub title: String,
+    #[serde(rename = "summary", skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(rename = "pages", default = "default_Report_pages")]
+    pub pages: Vec<String>,
+}
+
+fn default_Report_version() -> i32 {
+    1
+}
+
+fn default_Report_pages() -> Vec<String> {
+    vec![]
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Labeled<T> {
+    #[serde(rename = "label")]
+    pub label: String,
+    #[serde(rename = "value")]
+    pub value: T,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Score {
+    #[serde(rename = "label")]
+    pub label: String,
+    #[serde(rename = "value")]
+    pub value: f64,
+    #[serde(rename = "max")]
+    pub max: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Marker {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "version", default = "default_Marker_version")]
+    pub version: i32,
+}
+
+fn default_Marker_version() -> i32 {
+    1
+}
\ No newline at end of file
@@ -0,0 +1,62 @@
+type Optional<T> = T | null;
+
+/**
+ * Fields shared by every stored record
+ */
+export interface Entity {
+  id: string;
+  version: number;
+}
+
+/** Returns the default values of the fields of `Entity` that have one */
+export function defaultsEntity(): Partial<Entity> {
+  return {
+    version: 1,
+  };
+}
+
+/**
+ * Inherits the fields of `Entity` before its own
+ */
+export interface Document extends Entity {
+  title: string;
+  summary?: string;
+}
+
+/** Returns the default values of the fields of `Document` that have one */
+export function defaultsDocument(): Partial<Document> {
+  return {
+    version: 1,
+  };
+}
+
+export interface Report extends Document {
+  pages: Array<string>;
+}
+
+/** Returns the default values of the fields of `Report` that have one */
+export function defaultsReport(): Partial<Report> {
+  return {
+    version: 1,
+    pages: [],
+  };
+}
+
+export interface Labeled<T> {
+  label: string;
+  value: T;
+}
+
+export interface Score extends Labeled<number> {
+  max: Optional<number>;
+}
+
+export interface Marker extends Entity {
+}
+
+/** Returns the default values of the fields of `Marker` that have one */
+export function defaultsMarker(): Partial<Marker> {
+  return {
+    version: 1,
+  };
+}
\ No newline at end of file
@@ -0,0 +1,228 @@
+package golden
+
+import (
+    "context"
+    "encoding/json"
+    "errors"
+    "fmt"
+    "time"
+)
+
+// Not every schema needs every import
+var _ = context.Background
+var _ = json.Marshal
+var _ = errors.New
+var _ = fmt.Sprint
+var _ = time.Now
+
+// How urgent a message is
+type Priority int32
+
+const (
+    PriorityLow Priority = 0
+    PriorityNormal Priority = 1
+    PriorityHigh Priority = 2
+)
+
+func (v Priority) String() string {
+    switch v {
+    case PriorityLow:
+        return "Low"
+    case PriorityNormal:
+        return "Normal"
+    case PriorityHigh:
+        return "High"
+    }
+    return fmt.Sprint("Priority(", int32(v), ")")
+}
+
+func (v Priority) MarshalJSON() ([]byte, error) {
+    return json.Marshal(int32(v))
+}
+
+func (v *Priority) UnmarshalJSON(data []byte) error {
+    var raw int32
+    if err := json.Unmarshal(data, &raw); err != nil {
+        return err
+    }
+    switch Priority(raw) {
+    case PriorityLow, PriorityNormal, PriorityHigh:
+        *v = Priority(raw)
+        return nil
+    }
+    return errors.New(fmt.Sprint("invalid Priority value ", raw))
+}
+
+type Channel string
+
+const (
+    ChannelEmail Channel = "email"
+    ChannelSms Channel = "sms"
+)
+
+func (v Channel) String() string {
+    return string(v)
+}
+
+func (v Channel) MarshalJSON() ([]byte, error) {
+    return json.Marshal(string(v))
+}
+
+func (v *Channel) UnmarshalJSON(data []byte) error {
+    var raw string
+    if err := json.Unmarshal(data, &raw); err != nil {
+        return err
+    }
+    switch Channel(raw) {
+    case ChannelEmail, ChannelSms:
+        *v = Channel(raw)
+        return nil
+    }
+    return errors.New(fmt.Sprint("invalid Channel value ", raw))
+}
+
+// A message waiting to be delivered
+type Message struct {
+    Id string `json:"message_id"`
+    Body string `json:"body"`
+    // Left out for drafts
+    SentAt *int32 `json:"sentAt,omitempty"`
+    ReplyTo *string `json:"replyTo"`
+    Priority Priority `json:"priority"`
+    Channels []Channel `json:"channels"`
+    Headers map[string]string `json:"headers"`
+    Position []any `json:"position"`
+    // Deprecated: 
+    Legacy *bool `json:"legacy"`
+}
+
+type plainMessage Message
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Message) UnmarshalJSON(data []byte) error {
+    decoded := plainMessage{
+        Priority: PriorityNormal,
+        Channels: []Channel{},
+        Position: []any{0, 0},
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Message(decoded)
+    return nil
+}
+
+type Attachment struct {
+    Variant AttachmentVariant
+}
+
+// AttachmentVariant is any variant of Attachment
+type AttachmentVariant interface {
+    isVariantOfAttachment()
+}
+
+type AttachmentFile struct {
+    Name string `json:"name"`
+    Size int32 `json:"size"`
+}
+
+func (AttachmentFile) isVariantOfAttachment() {}
+
+func (v AttachmentFile) MarshalJSON() ([]byte, error) {
+    type plain AttachmentFile
+    return json.Marshal(struct {
+        Tag string `json:"kind"`
+        plain
+    }{"File", plain(v)})
+}
+
+type AttachmentLink struct {
+    Url string `json:"url"`
+}
+
+func (AttachmentLink) isVariantOfAttachment() {}
+
+func (v AttachmentLink) MarshalJSON() ([]byte, error) {
+    type plain AttachmentLink
+    return json.Marshal(struct {
+        Tag string `json:"kind"`
+        plain
+    }{"Link", plain(v)})
+}
+
+type AttachmentEmpty struct {
+}
+
+func (AttachmentEmpty) isVariantOfAttachment() {}
+
+func (v AttachmentEmpty) MarshalJSON() ([]byte, error) {
+    type plain AttachmentEmpty
+    return json.Marshal(struct {
+        Tag string `json:"kind"`
+        plain
+    }{"Empty", plain(v)})
+}
+
+func (v Attachment) MarshalJSON() ([]byte, error) {
+    return json.Marshal(v.Variant)
+}
+
+func (v *Attachment) UnmarshalJSON(data []byte) error {
+    variant, err := DecodeAttachment(data)
+    v.Variant = variant
+    return err
+}
+
+// DecodeAttachment decodes any variant of Attachment, selected by its "kind" field
+func DecodeAttachment(data []byte) (AttachmentVariant, error) {
+    var head struct {
+        Tag string `json:"kind"`
+    }
+    if err := json.Unmarshal(data, &head); err != nil {
+        return nil, err
+    }
+    switch head.Tag {
+    case "File":
+        var v AttachmentFile
+        err := json.Unmarshal(data, &v)
+        return v, err
+    case "Link":
+        var v AttachmentLink
+        err := json.Unmarshal(data, &v)
+        return v, err
+    case "Empty":
+        var v AttachmentEmpty
+        err := json.Unmarshal(data, &v)
+        return v, err
+    }
+    return nil, errors.New("unknown Attachment variant " + head.Tag)
+}
+
+type Batch[T any] struct {
+    Items []T `json:"items"`
+    Total int32 `json:"total"`
+}
+
+type plainBatch[T any] Batch[T]
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Batch[T]) UnmarshalJSON(data []byte) error {
+    decoded := plainBatch[T]{
+        Total: 0,
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Batch[T](decoded)
+    return nil
+}
+
+type Inbox = Batch[Message]
+
+type Tags = map[string]*[]string
+
+type Mailer interface {
+    // Queues a message for delivery
+    Send(ctx context.Context, request Message) (Message, error)
+    List(ctx context.Context, request int32) (Inbox, error)
+}
\ No newline at end of file
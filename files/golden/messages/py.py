@@ -0,0 +1,451 @@
+from __future__ import annotations
+from typing import Any, Callable, Final, Generic, Optional, List, Dict, NoReturn, Tuple, TypeVar, Union, Literal, Protocol
+
+import base64
+import binascii
+import datetime
+import decimal
+import re
+import uuid
+from enum import Enum
+from dataclasses import dataclass, field as _field
+
+_T = TypeVar("_T")
+
+_Path = Tuple[Union[str, int], ...]
+_Decoder = Callable[[Any, _Path], _T]
+_Encoder = Callable[[Any], Any]
+
+
+class DecodeError(ValueError):
+    """Raised when a value does not match the schema. `path` leads to the offending value"""
+
+    def __init__(self, path: _Path, message: str):
+        where = "".join(f"[{key}]" if isinstance(key, int) else f".{key}" for key in path)
+        super().__init__(f"${where}: {message}")
+        self.path = path
+
+
+def _fail(path: _Path, expected: str, value: Any) -> NoReturn:
+    raise DecodeError(path, f"expected {expected}, found {value!r}")
+
+
+def _decode_str(value: Any, path: _Path) -> str:
+    if not isinstance(value, str):
+        _fail(path, "a string", value)
+    return value
+
+
+def _decode_int(value: Any, path: _Path) -> int:
+    if not isinstance(value, int) or isinstance(value, bool):
+        _fail(path, "an integer", value)
+    return value
+
+
+def _decode_int_range(min: int, max: int) -> _Decoder[int]:
+    def decode(value: Any, path: _Path) -> int:
+        if not min <= _decode_int(value, path) <= max:
+            _fail(path, f"an integer between {min} and {max}", value)
+        return value
+
+    return decode
+
+
+def _decode_int_string(min: int, max: int) -> _Decoder[int]:
+    """64 bit integers are sent as strings, since a JSON number can not hold every value"""
+
+    def decode(value: Any, path: _Path) -> int:
+        if not isinstance(value, str) or not re.fullmatch(r"-?[0-9]+", value) or not min <= int(value) <= max:
+            _fail(path, f"a string holding an integer between {min} and {max}", value)
+        return int(value)
+
+    return decode
+
+
+def _decode_bytes(value: Any, path: _Path) -> bytes:
+    if isinstance(value, str):
+        try:
+            return base64.b64decode(value, validate=True)
+        except binascii.Error:
+            pass
+    _fail(path, "a base64 string", value)
+
+
+def _decode_timestamp(value: Any, path: _Path) -> datetime.datetime:
+    if isinstance(value, str):
+        try:
+            # fromisoformat only accepts a `Z` suffix from python 3.11
+            result = datetime.datetime.fromisoformat(re.sub(r"[Zz]$", "+00:00", value))
+            if result.tzinfo is not None:
+                return result
+        except ValueError:
+            pass
+    _fail(path, "an RFC 3339 timestamp", value)
+
+
+def _decode_date(value: Any, path: _Path) -> datetime.date:
+    if isinstance(value, str) and re.fullmatch(r"[0-9]{4}-[0-9]{2}-[0-9]{2}", value):
+        try:
+            return datetime.date.fromisoformat(value)
+        except ValueError:
+            pass
+    _fail(path, "a date", value)
+
+
+def _decode_duration(value: Any, path: _Path) -> datetime.timedelta:
+    return datetime.timedelta(seconds=_decode_float(value, path))
+
+
+def _decode_uuid(value: Any, path: _Path) -> uuid.UUID:
+    if isinstance(value, str):
+        try:
+            return uuid.UUID(value)
+        except ValueError:
+            pass
+    _fail(path, "a UUID", value)
+
+
+def _decode_decimal(value: Any, path: _Path) -> decimal.Decimal:
+    if isinstance(value, str) and re.fullmatch(r"-?[0-9]+(\.[0-9]+)?", value):
+        return decimal.Decimal(value)
+    _fail(path, "a decimal string", value)
+
+
+def _decode_float(value: Any, path: _Path) -> float:
+    if not isinstance(value, (int, float)) or isinstance(value, bool):
+        _fail(path, "a number", value)
+    return float(value)
+
+
+def _decode_bool(value: Any, path: _Path) -> bool:
+    if not isinstance(value, bool):
+        _fail(path, "a boolean", value)
+    return value
+
+
+def _decode_object(value: Any, path: _Path) -> Dict[str, Any]:
+    if not isinstance(value, dict):
+        _fail(path, "an object", value)
+    return value
+
+
+def _decode_field(obj: Dict[str, Any], path: _Path, key: str, decode: _Decoder[_T]) -> _T:
+    if key not in obj:
+        raise DecodeError(path, f"missing field {key!r}")
+    return decode(obj[key], (*path, key))
+
+
+def _decode_field_or(
+    obj: Dict[str, Any], path: _Path, key: str, decode: _Decoder[_T], default: Callable[[], _T]
+) -> _T:
+    if key not in obj:
+        return default()
+    return decode(obj[key], (*path, key))
+
+
+def _decode_enum(enum: Any, value: Any, path: _Path) -> Any:
+    if not isinstance(value, bool):
+        try:
+            return enum(value)
+        except ValueError:
+            pass
+    _fail(path, f"a member of {enum.__name__}", value)
+
+
+def _decode_nullable(inner: _Decoder[_T]) -> _Decoder[Optional[_T]]:
+    return lambda value, path: None if value is None else inner(value, path)
+
+
+def _decode_list(inner: _Decoder[_T]) -> _Decoder[List[_T]]:
+    def decode(value: Any, path: _Path) -> List[_T]:
+        if not isinstance(value, list):
+            _fail(path, "a list", value)
+        return [inner(item, (*path, i)) for i, item in enumerate(value)]
+
+    return decode
+
+
+def _decode_dict(inner: _Decoder[_T]) -> _Decoder[Dict[str, _T]]:
+    def decode(value: Any, path: _Path) -> Dict[str, _T]:
+        obj = _decode_object(value, path)
+        return {key: inner(item, (*path, key)) for key, item in obj.items()}
+
+    return decode
+
+
+def _decode_tuple(*items: _Decoder[Any]) -> _Decoder[Tuple[Any, ...]]:
+    def decode(value: Any, path: _Path) -> Tuple[Any, ...]:
+        if not isinstance(value, list) or len(value) != len(items):
+            _fail(path, f"a list of {len(items)} items", value)
+        return tuple(inner(item, (*path, i)) for i, (inner, item) in enumerate(zip(items, value)))
+
+    return decode
+
+
+def _encode_value(value: Any) -> Any:
+    return value
+
+
+def _encode_bytes(value: bytes) -> str:
+    return base64.b64encode(value).decode("ascii")
+
+
+def _encode_iso(value: Union[datetime.datetime, datetime.date]) -> str:
+    return value.isoformat()
+
+
+def _encode_duration(value: datetime.timedelta) -> float:
+    return value.total_seconds()
+
+
+def _encode_str(value: Any) -> str:
+    return str(value)
+
+
+def _encode_decimal(value: decimal.Decimal) -> str:
+    # str() may use an exponent, `1E+3`, which decimal strings do not allow
+    return format(value, "f")
+
+
+def _encode_nullable(inner: _Encoder) -> _Encoder:
+    return lambda value: None if value is None else inner(value)
+
+
+def _encode_list(inner: _Encoder) -> _Encoder:
+    return lambda value: [inner(item) for item in value]
+
+
+def _encode_dict(inner: _Encoder) -> _Encoder:
+    return lambda value: {key: inner(item) for key, item in value.items()}
+
+
+def _encode_tuple(*items: _Encoder) -> _Encoder:
+    return lambda value: [inner(item) for inner, item in zip(items, value)]
+
+class Priority(Enum):
+    """How urgent a message is"""
+
+    Low = 0
+    Normal = 1
+    High = 2
+
+
+def decode_Priority(value: Any, path: _Path = ()) -> Priority:
+    return _decode_enum(Priority, value, path)
+
+
+def encode_Priority(value: Priority) -> Any:
+    return value.value
+
+class Channel(Enum):
+    Email = "email"
+    Sms = "sms"
+
+
+def decode_Channel(value: Any, path: _Path = ()) -> Channel:
+    return _decode_enum(Channel, value, path)
+
+
+def encode_Channel(value: Channel) -> Any:
+    return value.value
+
+@dataclass(kw_only=True)
+class Message:
+    """A message waiting to be delivered"""
+    id: str
+    body: str
+    sent_at: Optional[int] = None
+    """Left out for drafts"""
+    reply_to: Optional[str]
+    priority: Priority = Priority.Normal
+    channels: List[Channel] = _field(default_factory=lambda: [])
+    headers: Dict[str, str]
+    position: Tuple[int, int] = (0, 0,)
+    legacy: Optional[bool]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return encode_Message(self)
+
+    @classmethod
+    def from_dict(cls, data: Any) -> Message:
+        return decode_Message(data)
+
+
+def decode_Message(value: Any, path: _Path = ()) -> Message:
+    obj = _decode_object(value, path)
+    return Message(
+        id=_decode_field(obj, path, "message_id", _decode_str),
+        body=_decode_field(obj, path, "body", _decode_str),
+        sent_at=_decode_field_or(obj, path, "sentAt", _decode_int_range(-2147483648, 2147483647), lambda: None),
+        reply_to=_decode_field(obj, path, "replyTo", _decode_nullable(_decode_str)),
+        priority=_decode_field_or(obj, path, "priority", decode_Priority, lambda: Priority.Normal),
+        channels=_decode_field_or(obj, path, "channels", _decode_list(decode_Channel), lambda: []),
+        headers=_decode_field(obj, path, "headers", _decode_dict(_decode_str)),
+        position=_decode_field_or(obj, path, "position", _decode_tuple(_decode_int_range(-2147483648, 2147483647), _decode_int_range(-2147483648, 2147483647)), lambda: (0, 0,)),
+        legacy=_decode_field(obj, path, "legacy", _decode_nullable(_decode_bool)),
+    )
+
+
+def encode_Message(value: Message) -> Dict[str, Any]:
+    return {
+        "message_id": _encode_value(value.id),
+        "body": _encode_value(value.body),
+        **({"sentAt": _encode_value(value.sent_at)} if value.sent_at is not None else {}),
+        "replyTo": _encode_nullable(_encode_value)(value.reply_to),
+        "priority": encode_Priority(value.priority),
+        "channels": _encode_list(encode_Channel)(value.channels),
+        "headers": _encode_dict(_encode_value)(value.headers),
+        "position": _encode_tuple(_encode_value, _encode_value)(value.position),
+        "legacy": _encode_nullable(_encode_value)(value.legacy),
+    }
+
+@dataclass
+class AttachmentFile:
+    name: str
+    size: int
+    kind: Literal["File"] = "File"
+
+    def to_dict(self) -> Dict[str, Any]:
+        return encode_AttachmentFile(self)
+
+    @classmethod
+    def from_dict(cls, data: Any) -> AttachmentFile:
+        return decode_AttachmentFile(data)
+
+
+def decode_AttachmentFile(value: Any, path: _Path = ()) -> AttachmentFile:
+    obj = _decode_object(value, path)
+    return AttachmentFile(
+        name=_decode_field(obj, path, "name", _decode_str),
+        size=_decode_field(obj, path, "size", _decode_int_range(-2147483648, 2147483647)),
+    )
+
+
+def encode_AttachmentFile(value: AttachmentFile) -> Dict[str, Any]:
+    return {
+        "kind": value.kind,
+        "name": _encode_value(value.name),
+        "size": _encode_value(value.size),
+    }
+
+@dataclass
+class AttachmentLink:
+    url: str
+    kind: Literal["Link"] = "Link"
+
+    def to_dict(self) -> Dict[str, Any]:
+        return encode_AttachmentLink(self)
+
+    @classmethod
+    def from_dict(cls, data: Any) -> AttachmentLink:
+        return decode_AttachmentLink(data)
+
+
+def decode_AttachmentLink(value: Any, path: _Path = ()) -> AttachmentLink:
+    obj = _decode_object(value, path)
+    return AttachmentLink(
+        url=_decode_field(obj, path, "url", _decode_str),
+    )
+
+
+def encode_AttachmentLink(value: AttachmentLink) -> Dict[str, Any]:
+    return {
+        "kind": value.kind,
+        "url": _encode_value(value.url),
+    }
+
+@dataclass
+class AttachmentEmpty:
+    kind: Literal["Empty"] = "Empty"
+
+    def to_dict(self) -> Dict[str, Any]:
+        return encode_AttachmentEmpty(self)
+
+    @classmethod
+    def from_dict(cls, data: Any) -> AttachmentEmpty:
+        return decode_AttachmentEmpty(data)
+
+
+def decode_AttachmentEmpty(value: Any, path: _Path = ()) -> AttachmentEmpty:
+    obj = _decode_object(value, path)
+    return AttachmentEmpty(
+    )
+
+
+def encode_AttachmentEmpty(value: AttachmentEmpty) -> Dict[str, Any]:
+    return {
+        "kind": value.kind,
+    }
+
+Attachment = Union[AttachmentFile, AttachmentLink, AttachmentEmpty]
+
+
+def decode_Attachment(value: Any, path: _Path = ()) -> Attachment:
+    obj = _decode_object(value, path)
+    variants: Dict[str, _Decoder[Attachment]] = {
+        "File": decode_AttachmentFile,
+        "Link": decode_AttachmentLink,
+        "Empty": decode_AttachmentEmpty,
+    }
+    tag = obj.get("kind")
+    if not isinstance(tag, str) or tag not in variants:
+        _fail((*path, "kind"), "a variant of Attachment", tag)
+    return variants[tag](obj, path)
+
+
+def encode_Attachment(value: Attachment) -> Dict[str, Any]:
+    return value.to_dict()
+
+T = TypeVar("T")
+
+
+@dataclass(kw_only=True)
+class Batch(Generic[T]):
+    items: List[T]
+    total: int = 0
+
+
+def decode_Batch(decode_T: _Decoder[T]) -> _Decoder[Batch[T]]:
+    def decode(value: Any, path: _Path = ()) -> Batch[T]:
+        obj = _decode_object(value, path)
+        return Batch(
+            items=_decode_field(obj, path, "items", _decode_list(decode_T)),
+            total=_decode_field_or(obj, path, "total", _decode_int_range(-2147483648, 2147483647), lambda: 0),
+        )
+
+    return decode
+
+
+def encode_Batch(encode_T: _Encoder) -> _Encoder:
+    def encode(value: Batch[T]) -> Dict[str, Any]:
+        return {
+            "items": _encode_list(encode_T)(value.items),
+            "total": _encode_value(value.total),
+        }
+
+    return encode
+
+Inbox = Batch[Message]
+
+
+def decode_Inbox(value: Any, path: _Path = ()) -> Inbox:
+    return decode_Batch(decode_Message)(value, path)
+
+
+def encode_Inbox(value: Inbox) -> Any:
+    return encode_Batch(encode_Message)(value)
+
+Tags = Dict[str, Optional[List[str]]]
+
+
+def decode_Tags(value: Any, path: _Path = ()) -> Tags:
+    return _decode_dict(_decode_nullable(_decode_list(_decode_str)))(value, path)
+
+
+def encode_Tags(value: Tags) -> Any:
+    return _encode_dict(_encode_nullable(_encode_list(_encode_value)))(value)
+
+class Mailer(Protocol):
+    def send(self, request: Message) -> Message:
+        """Queues a message for delivery"""
+    def list(self, request: int) -> Inbox: ...
\ No newline at end of file
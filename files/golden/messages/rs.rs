@@ -0,0 +1,101 @@
+#![allow(non_snake_case)]
+#![allow(unused_imports)]
+#![allow(deprecated)]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// How urgent a message is
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Priority {
+    Low = 0,
+    Normal = 1,
+    High = 2,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "sms")]
+    Sms,
+}
+
+/// A message waiting to be delivered
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Message {
+    #[serde(rename = "message_id")]
+    pub id: String,
+    #[serde(rename = "body")]
+    pub body: String,
+    /// Left out for drafts
+    #[serde(rename = "sentAt", skip_serializing_if = "Option::is_none")]
+    pub sent_at: Option<i32>,
+    #[serde(rename = "replyTo")]
+    pub reply_to: Option<String>,
+    #[serde(rename = "priority", default = "default_Message_priority")]
+    pub priority: Priority,
+    #[serde(rename = "channels", default = "default_Message_channels")]
+    pub channels: Vec<Channel>,
+    #[serde(rename = "headers")]
+    pub headers: HashMap<String, String>,
+    #[serde(rename = "position", default = "default_Message_position")]
+    pub position: (i32, i32),
+    #[deprecated(note = "")]
+    #[serde(rename = "legacy")]
+    pub legacy: Option<bool>,
+}
+
+fn default_Message_priority() -> Priority {
+    Priority::Normal
+}
+
+fn default_Message_channels() -> Vec<Channel> {
+    vec![]
+}
+
+fn default_Message_position() -> (i32, i32) {
+    (0, 0,)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind")]
+pub enum Attachment {
+    File {
+        #[serde(rename = "name")]
+        name: String,
+        #[serde(rename = "size")]
+        size: i32,
+    },
+    Link {
+        #[serde(rename = "url")]
+        url: String,
+    },
+    Empty {
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Batch<T> {
+    #[serde(rename = "items")]
+    pub items: Vec<T>,
+    #[serde(rename = "total", default = "default_Batch_total::<T>")]
+    pub total: i32,
+}
+
+fn default_Batch_total<T>() -> i32 {
+    0
+}
+
+pub type Inbox = Batch<Message>;
+
+pub type Tags = HashMap<String, Option<Vec<String>>>;
+
+pub trait Mailer {
+    /// Queues a message for delivery
+    fn send(&self, request: Message) -> Message;
+    fn list(&self, request: i32) -> Inbox;
+}
\ No newline at end of file
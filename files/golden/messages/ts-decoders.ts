@@ -0,0 +1,268 @@
+type Optional<T> = T | null;
+
+export type DecodePath = (string | number)[];
+
+export interface DecodeError extends Error {
+  name: "DecodeError";
+  path: DecodePath;
+}
+
+export function isDecodeError(error: unknown): error is DecodeError {
+  return error instanceof Error && error.name === "DecodeError";
+}
+
+export type Decoder<T> = (value: unknown, path: DecodePath) => T;
+
+function fail(path: DecodePath, expected: string, value: unknown): never {
+  const at = path.map((key) => (typeof key === "number" ? `[${key}]` : `.${key}`)).join("");
+  const error = new Error(`$${at}: expected ${expected}, found ${JSON.stringify(value)}`) as DecodeError;
+  error.name = "DecodeError";
+  error.path = path;
+  throw error;
+}
+
+function decodeString(value: unknown, path: DecodePath): string {
+  return typeof value === "string" ? value : fail(path, "a string", value);
+}
+
+function decodeInt(value: unknown, path: DecodePath): number {
+  return Number.isInteger(value) ? (value as number) : fail(path, "an integer", value);
+}
+
+function decodeIntRange(min: number, max: number): Decoder<number> {
+  return (value, path) => {
+    const int = decodeInt(value, path);
+    return int >= min && int <= max ? int : fail(path, `an integer between ${min} and ${max}`, value);
+  };
+}
+
+// 64 bit integers are sent as strings, since a number can not hold every value
+function decodeInt64(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^-?[0-9]+$/.test(value) ? value : fail(path, "an integer string", value);
+}
+
+function decodeUint64(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^[0-9]+$/.test(value) ? value : fail(path, "an unsigned integer string", value);
+}
+
+function decodeBytes(value: unknown, path: DecodePath): string {
+  const base64 = /^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$/;
+  return typeof value === "string" && base64.test(value) ? value : fail(path, "a base64 string", value);
+}
+
+function decodeTimestamp(value: unknown, path: DecodePath): Date {
+  const rfc3339 = /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$/;
+  const date = typeof value === "string" && rfc3339.test(value) ? new Date(value) : undefined;
+  return date !== undefined && !Number.isNaN(date.getTime()) ? date : fail(path, "an RFC 3339 timestamp", value);
+}
+
+function decodeDate(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^\d{4}-\d{2}-\d{2}$/.test(value) ? value : fail(path, "a date", value);
+}
+
+function decodeUuid(value: unknown, path: DecodePath): string {
+  const uuid = /^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$/;
+  return typeof value === "string" && uuid.test(value) ? value : fail(path, "a UUID", value);
+}
+
+function decodeDecimal(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^-?[0-9]+(\.[0-9]+)?$/.test(value) ? value : fail(path, "a decimal string", value);
+}
+
+function decodeFloat(value: unknown, path: DecodePath): number {
+  return typeof value === "number" ? value : fail(path, "a number", value);
+}
+
+function decodeBool(value: unknown, path: DecodePath): boolean {
+  return typeof value === "boolean" ? value : fail(path, "a boolean", value);
+}
+
+function decodeObject(value: unknown, path: DecodePath): Record<string, unknown> {
+  if (typeof value !== "object" || value === null || Array.isArray(value)) {
+    return fail(path, "an object", value);
+  }
+  return value as Record<string, unknown>;
+}
+
+function decodeNullable<T>(inner: Decoder<T>): Decoder<Optional<T>> {
+  return (value, path) => (value === null ? null : inner(value, path));
+}
+
+function decodeArray<T>(inner: Decoder<T>): Decoder<Array<T>> {
+  return (value, path) => {
+    if (!Array.isArray(value)) {
+      return fail(path, "an array", value);
+    }
+    return value.map((item, i) => inner(item, [...path, i]));
+  };
+}
+
+function decodeMap<T>(inner: Decoder<T>): Decoder<Record<string, T>> {
+  return (value, path) => {
+    const obj = decodeObject(value, path);
+    const result: Record<string, T> = {};
+    for (const key of Object.keys(obj)) {
+      result[key] = inner(obj[key], [...path, key]);
+    }
+    return result;
+  };
+}
+
+function decodeTuple<T extends unknown[]>(...items: { [K in keyof T]: Decoder<T[K]> }): Decoder<T> {
+  return (value, path) => {
+    if (!Array.isArray(value) || value.length !== items.length) {
+      return fail(path, `an array of ${items.length} items`, value);
+    }
+    return (items as Decoder<unknown>[]).map((item, i) => item(value[i], [...path, i])) as T;
+  };
+}
+
+/**
+ * How urgent a message is
+ */
+export enum Priority {
+  Low = 0,
+  Normal = 1,
+  High = 2
+}
+
+export function decodePriority(value: unknown, path: DecodePath = []): Priority {
+  const members: unknown[] = [Priority.Low, Priority.Normal, Priority.High];
+  return members.includes(value) ? (value as Priority) : fail(path, "a member of Priority", value);
+}
+
+export enum Channel {
+  Email = "email",
+  Sms = "sms"
+}
+
+export function decodeChannel(value: unknown, path: DecodePath = []): Channel {
+  const members: unknown[] = [Channel.Email, Channel.Sms];
+  return members.includes(value) ? (value as Channel) : fail(path, "a member of Channel", value);
+}
+
+/**
+ * A message waiting to be delivered
+ */
+export interface Message {
+  message_id: string;
+  body: string;
+  /**
+   * Left out for drafts
+   */
+  sentAt?: number;
+  replyTo: Optional<string>;
+  priority: Priority;
+  channels: Array<Channel>;
+  headers: Record<string, string>;
+  position: [number, number];
+  /**
+   * @deprecated 
+   */
+  legacy: Optional<boolean>;
+}
+
+/** Returns the default values of the fields of `Message` that have one */
+export function defaultsMessage(): Partial<Message> {
+  return {
+    priority: Priority.Normal,
+    channels: [],
+    position: [0, 0],
+  };
+}
+
+export function decodeMessage(value: unknown, path: DecodePath = []): Message {
+  const obj = decodeObject(value, path);
+  return {
+    message_id: decodeString(obj["message_id"], [...path, "message_id"]),
+    body: decodeString(obj["body"], [...path, "body"]),
+    sentAt: Object.prototype.hasOwnProperty.call(obj, "sentAt") ? decodeIntRange(-2147483648, 2147483647)(obj["sentAt"], [...path, "sentAt"]) : undefined,
+    replyTo: decodeNullable(decodeString)(obj["replyTo"], [...path, "replyTo"]),
+    priority: Object.prototype.hasOwnProperty.call(obj, "priority") ? decodePriority(obj["priority"], [...path, "priority"]) : Priority.Normal,
+    channels: Object.prototype.hasOwnProperty.call(obj, "channels") ? decodeArray(decodeChannel)(obj["channels"], [...path, "channels"]) : [],
+    headers: decodeMap(decodeString)(obj["headers"], [...path, "headers"]),
+    position: Object.prototype.hasOwnProperty.call(obj, "position") ? decodeTuple(decodeIntRange(-2147483648, 2147483647), decodeIntRange(-2147483648, 2147483647))(obj["position"], [...path, "position"]) : [0, 0],
+    legacy: decodeNullable(decodeBool)(obj["legacy"], [...path, "legacy"]),
+  };
+}
+
+export interface AttachmentFile {
+  kind: "File";
+  name: string;
+  size: number;
+}
+
+export interface AttachmentLink {
+  kind: "Link";
+  url: string;
+}
+
+export interface AttachmentEmpty {
+  kind: "Empty";
+}
+
+export type Attachment = AttachmentFile | AttachmentLink | AttachmentEmpty;
+
+export function decodeAttachment(value: unknown, path: DecodePath = []): Attachment {
+  const obj = decodeObject(value, path);
+  switch (obj["kind"]) {
+    case "File":
+      return {
+        kind: "File",
+        name: decodeString(obj["name"], [...path, "name"]),
+        size: decodeIntRange(-2147483648, 2147483647)(obj["size"], [...path, "size"]),
+      };
+    case "Link":
+      return {
+        kind: "Link",
+        url: decodeString(obj["url"], [...path, "url"]),
+      };
+    case "Empty":
+      return {
+        kind: "Empty",
+      };
+  }
+  return fail([...path, "kind"], "a variant of Attachment", obj["kind"]);
+}
+
+export interface Batch<T> {
+  items: Array<T>;
+  total: number;
+}
+
+/** Returns the default values of the fields of `Batch` that have one */
+export function defaultsBatch<T>(): Partial<Batch<T>> {
+  return {
+    total: 0,
+  };
+}
+
+export function decodeBatch<T>(decodeT: Decoder<T>): Decoder<Batch<T>> {
+  return (value, path) => {
+    const obj = decodeObject(value, path);
+    return {
+      items: decodeArray(decodeT)(obj["items"], [...path, "items"]),
+      total: Object.prototype.hasOwnProperty.call(obj, "total") ? decodeIntRange(-2147483648, 2147483647)(obj["total"], [...path, "total"]) : 0,
+    };
+  };
+}
+
+export type Inbox = Batch<Message>;
+
+export function decodeInbox(value: unknown, path: DecodePath = []): Inbox {
+  return decodeBatch(decodeMessage)(value, path);
+}
+
+export type Tags = Record<string, Optional<Array<string>>>;
+
+export function decodeTags(value: unknown, path: DecodePath = []): Tags {
+  return decodeMap(decodeNullable(decodeArray(decodeString)))(value, path);
+}
+
+export interface Mailer {
+  /**
+   * Queues a message for delivery
+   */
+  send(request: Message): Promise<Message>;
+  list(request: number): Promise<Inbox>;
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+type Optional<T> = T | null;
+
+/**
+ * How urgent a message is
+ */
+export enum Priority {
+  Low = 0,
+  Normal = 1,
+  High = 2
+}
+
+export enum Channel {
+  Email = "email",
+  Sms = "sms"
+}
+
+/**
+ * A message waiting to be delivered
+ */
+export interface Message {
+  message_id: string;
+  body: string;
+  /**
+   * Left out for drafts
+   */
+  sentAt?: number;
+  replyTo: Optional<string>;
+  priority: Priority;
+  channels: Array<Channel>;
+  headers: Record<string, string>;
+  position: [number, number];
+  /**
+   * @deprecated 
+   */
+  legacy: Optional<boolean>;
+}
+
+/** Returns the default values of the fields of `Message` that have one */
+export function defaultsMessage(): Partial<Message> {
+  return {
+    priority: Priority.Normal,
+    channels: [],
+    position: [0, 0],
+  };
+}
+
+export interface AttachmentFile {
+  kind: "File";
+  name: string;
+  size: number;
+}
+
+export interface AttachmentLink {
+  kind: "Link";
+  url: string;
+}
+
+export interface AttachmentEmpty {
+  kind: "Empty";
+}
+
+export type Attachment = AttachmentFile | AttachmentLink | AttachmentEmpty;
+
+export interface Batch<T> {
+  items: Array<T>;
+  total: number;
+}
+
+/** Returns the default values of the fields of `Batch` that have one */
+export function defaultsBatch<T>(): Partial<Batch<T>> {
+  return {
+    total: 0,
+  };
+}
+
+export type Inbox = Batch<Message>;
+
+export type Tags = Record<string, Optional<Array<string>>>;
+
+export interface Mailer {
+  /**
+   * Queues a message for delivery
+   */
+  send(request: Message): Promise<Message>;
+  list(request: number): Promise<Inbox>;
+}
\ No newline at end of file
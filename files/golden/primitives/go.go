@@ -0,0 +1,57 @@
+package golden
+
+import (
+    "context"
+    "encoding/json"
+    "errors"
+    "fmt"
+    "time"
+)
+
+// Not every schema needs every import
+var _ = context.Background
+var _ = json.Marshal
+var _ = errors.New
+var _ = fmt.Sprint
+var _ = time.Now
+
+// Every number type, with a default value at one of its bounds
+type Numbers struct {
+    Tiny int8 `json:"tiny"`
+    Short int16 `json:"short"`
+    Regular int32 `json:"regular"`
+    Sized int32 `json:"sized"`
+    Long string `json:"long"`
+    Byte uint8 `json:"byte"`
+    Word uint16 `json:"word"`
+    Unsigned uint32 `json:"unsigned"`
+    Huge string `json:"huge"`
+    Single float32 `json:"single"`
+    Double float64 `json:"double"`
+    Blob []byte `json:"blob"`
+    Bytes []uint8 `json:"bytes"`
+}
+
+type plainNumbers Numbers
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Numbers) UnmarshalJSON(data []byte) error {
+    decoded := plainNumbers{
+        Tiny: -128,
+        Short: 32767,
+        Regular: -2147483648,
+        Sized: 2147483647,
+        Long: "-9223372036854775808",
+        Byte: 255,
+        Word: 65535,
+        Unsigned: 4294967295,
+        Huge: "18446744073709551615",
+        Single: 3.4028235e38,
+        Double: -1.5e-300,
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Numbers(decoded)
+    return nil
+}
\ No newline at end of file
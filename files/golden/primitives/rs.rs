@@ -0,0 +1,179 @@
+#![allow(non_snake_case)]
+#![allow(unused_imports)]
+#![allow(deprecated)]
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! string_int {
+    ($name:ident, $int:ty) => {
+        /// A 64 bit integer, sent as a string since JSON numbers can not hold every value
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        pub struct $name(pub $int);
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let text = String::deserialize(deserializer)?;
+                text.parse().map($name).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+string_int!(Int64, i64);
+string_int!(Uint64, u64);
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Binary data, sent as a base64 string
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut text = String::with_capacity((self.0.len() + 2) / 3 * 4);
+
+        for chunk in self.0.chunks(3) {
+            let bits = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |bits, (i, byte)| bits | (*byte as u32) << (16 - 8 * i));
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let index = (bits >> (18 - 6 * i) & 63) as usize;
+                    text.push(BASE64_ALPHABET[index] as char);
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+
+        serializer.serialize_str(&text)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let invalid = || serde::de::Error::custom("invalid base64");
+
+        if text.len() % 4 != 0 {
+            return Err(invalid());
+        }
+
+        let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+        let chunks = text.as_bytes().chunks(4);
+        let last = chunks.len().saturating_sub(1);
+
+        for (index, chunk) in chunks.enumerate() {
+            let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+            if padding > 2 || (padding > 0 && index != last) {
+                return Err(invalid());
+            }
+
+            let mut bits = 0u32;
+            for (i, c) in chunk[..4 - padding].iter().enumerate() {
+                let value = BASE64_ALPHABET.iter().position(|b| b == c).ok_or_else(invalid)?;
+                bits |= (value as u32) << (18 - 6 * i);
+            }
+
+            bytes.extend_from_slice(&bits.to_be_bytes()[1..4 - padding]);
+        }
+
+        Ok(Bytes(bytes))
+    }
+}
+
+/// Every number type, with a default value at one of its bounds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Numbers {
+    #[serde(rename = "tiny", default = "default_Numbers_tiny")]
+    pub tiny: i8,
+    #[serde(rename = "short", default = "default_Numbers_short")]
+    pub short: i16,
+    #[serde(rename = "regular", default = "default_Numbers_regular")]
+    pub regular: i32,
+    #[serde(rename = "sized", default = "default_Numbers_sized")]
+    pub sized: i32,
+    #[serde(rename = "long", default = "default_Numbers_long")]
+    pub long: Int64,
+    #[serde(rename = "byte", default = "default_Numbers_byte")]
+    pub byte: u8,
+    #[serde(rename = "word", default = "default_Numbers_word")]
+    pub word: u16,
+    #[serde(rename = "unsigned", default = "default_Numbers_unsigned")]
+    pub unsigned: u32,
+    #[serde(rename = "huge", default = "default_Numbers_huge")]
+    pub huge: Uint64,
+    #[serde(rename = "single", default = "default_Numbers_single")]
+    pub single: f32,
+    #[serde(rename = "double", default = "default_Numbers_double")]
+    pub double: f64,
+    #[serde(rename = "blob")]
+    pub blob: Bytes,
+    #[serde(rename = "bytes")]
+    pub bytes: Vec<u8>,
+}
+
+fn default_Numbers_tiny() -> i8 {
+    -128
+}
+
+fn default_Numbers_short() -> i16 {
+    32767
+}
+
+fn default_Numbers_regular() -> i32 {
+    -2147483648
+}
+
+fn default_Numbers_sized() -> i32 {
+    2147483647
+}
+
+fn default_Numbers_long() -> Int64 {
+    Int64(-9223372036854775808)
+}
+
+fn default_Numbers_byte() -> u8 {
+    255
+}
+
+fn default_Numbers_word() -> u16 {
+    65535
+}
+
+fn default_Numbers_unsigned() -> u32 {
+    4294967295
+}
+
+fn default_Numbers_huge() -> Uint64 {
+    Uint64(18446744073709551615)
+}
+
+fn default_Numbers_single() -> f32 {
+    3.4028235e38
+}
+
+fn default_Numbers_double() -> f64 {
+    -1.5e-300
+}
+
+pub const INT_MIN: i32 = -2147483648;
+
+pub const INT_MAX: i32 = 2147483647;
+
+pub const I64_MIN: i64 = -9223372036854775808;
+
+pub const I64_MAX: i64 = 9223372036854775807;
+
+pub const U64_MAX: u64 = 18446744073709551615;
+
+pub const U64_MIN: u64 = 0;
\ No newline at end of file
@@ -0,0 +1,185 @@
+type Optional<T> = T | null;
+
+export type DecodePath = (string | number)[];
+
+export interface DecodeError extends Error {
+  name: "DecodeError";
+  path: DecodePath;
+}
+
+export function isDecodeError(error: unknown): error is DecodeError {
+  return error instanceof Error && error.name === "DecodeError";
+}
+
+export type Decoder<T> = (value: unknown, path: DecodePath) => T;
+
+function fail(path: DecodePath, expected: string, value: unknown): never {
+  const at = path.map((key) => (typeof key === "number" ? `[${key}]` : `.${key}`)).join("");
+  const error = new Error(`$${at}: expected ${expected}, found ${JSON.stringify(value)}`) as DecodeError;
+  error.name = "DecodeError";
+  error.path = path;
+  throw error;
+}
+
+function decodeString(value: unknown, path: DecodePath): string {
+  return typeof value === "string" ? value : fail(path, "a string", value);
+}
+
+function decodeInt(value: unknown, path: DecodePath): number {
+  return Number.isInteger(value) ? (value as number) : fail(path, "an integer", value);
+}
+
+function decodeIntRange(min: number, max: number): Decoder<number> {
+  return (value, path) => {
+    const int = decodeInt(value, path);
+    return int >= min && int <= max ? int : fail(path, `an integer between ${min} and ${max}`, value);
+  };
+}
+
+// 64 bit integers are sent as strings, since a number can not hold every value
+function decodeInt64(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^-?[0-9]+$/.test(value) ? value : fail(path, "an integer string", value);
+}
+
+function decodeUint64(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^[0-9]+$/.test(value) ? value : fail(path, "an unsigned integer string", value);
+}
+
+function decodeBytes(value: unknown, path: DecodePath): string {
+  const base64 = /^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$/;
+  return typeof value === "string" && base64.test(value) ? value : fail(path, "a base64 string", value);
+}
+
+function decodeTimestamp(value: unknown, path: DecodePath): Date {
+  const rfc3339 = /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$/;
+  const date = typeof value === "string" && rfc3339.test(value) ? new Date(value) : undefined;
+  return date !== undefined && !Number.isNaN(date.getTime()) ? date : fail(path, "an RFC 3339 timestamp", value);
+}
+
+function decodeDate(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^\d{4}-\d{2}-\d{2}$/.test(value) ? value : fail(path, "a date", value);
+}
+
+function decodeUuid(value: unknown, path: DecodePath): string {
+  const uuid = /^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$/;
+  return typeof value === "string" && uuid.test(value) ? value : fail(path, "a UUID", value);
+}
+
+function decodeDecimal(value: unknown, path: DecodePath): string {
+  return typeof value === "string" && /^-?[0-9]+(\.[0-9]+)?$/.test(value) ? value : fail(path, "a decimal string", value);
+}
+
+function decodeFloat(value: unknown, path: DecodePath): number {
+  return typeof value === "number" ? value : fail(path, "a number", value);
+}
+
+function decodeBool(value: unknown, path: DecodePath): boolean {
+  return typeof value === "boolean" ? value : fail(path, "a boolean", value);
+}
+
+function decodeObject(value: unknown, path: DecodePath): Record<string, unknown> {
+  if (typeof value !== "object" || value === null || Array.isArray(value)) {
+    return fail(path, "an object", value);
+  }
+  return value as Record<string, unknown>;
+}
+
+function decodeNullable<T>(inner: Decoder<T>): Decoder<Optional<T>> {
+  return (value, path) => (value === null ? null : inner(value, path));
+}
+
+function decodeArray<T>(inner: Decoder<T>): Decoder<Array<T>> {
+  return (value, path) => {
+    if (!Array.isArray(value)) {
+      return fail(path, "an array", value);
+    }
+    return value.map((item, i) => inner(item, [...path, i]));
+  };
+}
+
+function decodeMap<T>(inner: Decoder<T>): Decoder<Record<string, T>> {
+  return (value, path) => {
+    const obj = decodeObject(value, path);
+    const result: Record<string, T> = {};
+    for (const key of Object.keys(obj)) {
+      result[key] = inner(obj[key], [...path, key]);
+    }
+    return result;
+  };
+}
+
+function decodeTuple<T extends unknown[]>(...items: { [K in keyof T]: Decoder<T[K]> }): Decoder<T> {
+  return (value, path) => {
+    if (!Array.isArray(value) || value.length !== items.length) {
+      return fail(path, `an array of ${items.length} items`, value);
+    }
+    return (items as Decoder<unknown>[]).map((item, i) => item(value[i], [...path, i])) as T;
+  };
+}
+
+/**
+ * Every number type, with a default value at one of its bounds
+ */
+export interface Numbers {
+  tiny: number;
+  short: number;
+  regular: number;
+  sized: number;
+  long: string;
+  byte: number;
+  word: number;
+  unsigned: number;
+  huge: string;
+  single: number;
+  double: number;
+  blob: string;
+  bytes: Array<number>;
+}
+
+/** Returns the default values of the fields of `Numbers` that have one */
+export function defaultsNumbers(): Partial<Numbers> {
+  return {
+    tiny: -128,
+    short: 32767,
+    regular: -2147483648,
+    sized: 2147483647,
+    long: "-9223372036854775808",
+    byte: 255,
+    word: 65535,
+    unsigned: 4294967295,
+    huge: "18446744073709551615",
+    single: 3.4028235e38,
+    double: -1.5e-300,
+  };
+}
+
+export function decodeNumbers(value: unknown, path: DecodePath = []): Numbers {
+  const obj = decodeObject(value, path);
+  return {
+    tiny: Object.prototype.hasOwnProperty.call(obj, "tiny") ? decodeIntRange(-128, 127)(obj["tiny"], [...path, "tiny"]) : -128,
+    short: Object.prototype.hasOwnProperty.call(obj, "short") ? decodeIntRange(-32768, 32767)(obj["short"], [...path, "short"]) : 32767,
+    regular: Object.prototype.hasOwnProperty.call(obj, "regular") ? decodeIntRange(-2147483648, 2147483647)(obj["regular"], [...path, "regular"]) : -2147483648,
+    sized: Object.prototype.hasOwnProperty.call(obj, "sized") ? decodeIntRange(-2147483648, 2147483647)(obj["sized"], [...path, "sized"]) : 2147483647,
+    long: Object.prototype.hasOwnProperty.call(obj, "long") ? decodeInt64(obj["long"], [...path, "long"]) : "-9223372036854775808",
+    byte: Object.prototype.hasOwnProperty.call(obj, "byte") ? decodeIntRange(0, 255)(obj["byte"], [...path, "byte"]) : 255,
+    word: Object.prototype.hasOwnProperty.call(obj, "word") ? decodeIntRange(0, 65535)(obj["word"], [...path, "word"]) : 65535,
+    unsigned: Object.prototype.hasOwnProperty.call(obj, "unsigned") ? decodeIntRange(0, 4294967295)(obj["unsigned"], [...path, "unsigned"]) : 4294967295,
+    huge: Object.prototype.hasOwnProperty.call(obj, "huge") ? decodeUint64(obj["huge"], [...path, "huge"]) : "18446744073709551615",
+    single: Object.prototype.hasOwnProperty.call(obj, "single") ? decodeFloat(obj["single"], [...path, "single"]) : 3.4028235e38,
+    double: Object.prototype.hasOwnProperty.call(obj, "double") ? decodeFloat(obj["double"], [...path, "double"]) : -1.5e-300,
+    blob: decodeBytes(obj["blob"], [...path, "blob"]),
+    bytes: decodeArray(decodeIntRange(0, 255))(obj["bytes"], [...path, "bytes"]),
+  };
+}
+
+export const INT_MIN: number = -2147483648;
+
+export const INT_MAX: number = 2147483647;
+
+export const I64_MIN: string = "-9223372036854775808";
+
+export const I64_MAX: string = "9223372036854775807";
+
+export const U64_MAX: string = "18446744073709551615";
+
+export const U64_MIN: string = "0";
\ No newline at end of file
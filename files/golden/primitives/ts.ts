@@ -0,0 +1,49 @@
+type Optional<T> = T | null;
+
+/**
+ * Every number type, with a default value at one of its bounds
+ */
+export interface Numbers {
+  tiny: number;
+  short: number;
+  regular: number;
+  sized: number;
+  long: string;
+  byte: number;
+  word: number;
+  unsigned: number;
+  huge: string;
+  single: number;
+  double: number;
+  blob: string;
+  bytes: Array<number>;
+}
+
+/** Returns the default values of the fields of `Numbers` that have one */
+export function defaultsNumbers(): Partial<Numbers> {
+  return {
+    tiny: -128,
+    short: 32767,
+    regular: -2147483648,
+    sized: 2147483647,
+    long: "-9223372036854775808",
+    byte: 255,
+    word: 65535,
+    unsigned: 4294967295,
+    huge: "18446744073709551615",
+    single: 3.4028235e38,
+    double: -1.5e-300,
+  };
+}
+
+export const INT_MIN: number = -2147483648;
+
+export const INT_MAX: number = 2147483647;
+
+export const I64_MIN: string = "-9223372036854775808";
+
+export const I64_MAX: string = "9223372036854775807";
+
+export const U64_MAX: string = "18446744073709551615";
+
+export const U64_MIN: string = "0";
\ No newline at end of file
@@ -0,0 +1,44 @@
+package golden
+
+import (
+    "context"
+    "encoding/json"
+    "errors"
+    "fmt"
+    "time"
+)
+
+// Not every schema needs every import
+var _ = context.Background
+var _ = json.Marshal
+var _ = errors.New
+var _ = fmt.Sprint
+var _ = time.Now
+
+// A payment, with every well-known type
+type Payment struct {
+    Id string `json:"id"`
+    Amount string `json:"amount"`
+    Receipt *string `json:"receipt"`
+    PaidAt time.Time `json:"paidAt"`
+    DueOn string `json:"dueOn"`
+    Window float64 `json:"window"`
+    RetryAfter float64 `json:"retryAfter"`
+    History []time.Time `json:"history"`
+    Notes *map[string]string `json:"notes,omitempty"`
+}
+
+type plainPayment Payment
+
+// UnmarshalJSON fills in the default values of the fields missing from data
+func (v *Payment) UnmarshalJSON(data []byte) error {
+    decoded := plainPayment{
+        Window: 86400.0,
+        RetryAfter: 0.5,
+    }
+    if err := json.Unmarshal(data, &decoded); err != nil {
+        return err
+    }
+    *v = Payment(decoded)
+    return nil
+}
\ No newline at end of file
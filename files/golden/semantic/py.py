@@ -0,0 +1,276 @@
+from __future__ import annotations
+from typing import Any, Callable, Final, Generic, Optional, List, Dict, NoReturn, Tuple, TypeVar, Union, Literal, Protocol
+
+import base64
+import binascii
+import datetime
+import decimal
+import re
+import uuid
+from enum import Enum
+from dataclasses import dataclass, field as _field
+
+_T = TypeVar("_T")
+
+_Path = Tuple[Union[str, int], ...]
+_Decoder = Callable[[Any, _Path], _T]
+_Encoder = Callable[[Any], Any]
+
+
+class DecodeError(ValueError):
+    """Raised when a value does not match the schema. `path` leads to the offending value"""
+
+    def __init__(self, path: _Path, message: str):
+        where = "".join(f"[{key}]" if isinstance(key, int) else f".{key}" for key in path)
+        super().__init__(f"${where}: {message}")
+        self.path = path
+
+
+def _fail(path: _Path, expected: str, value: Any) -> NoReturn:
+    raise DecodeError(path, f"expected {expected}, found {value!r}")
+
+
+def _decode_str(value: Any, path: _Path) -> str:
+    if not isinstance(value, str):
+        _fail(path, "a string", value)
+    return value
+
+
+def _decode_int(value: Any, path: _Path) -> int:
+    if not isinstance(value, int) or isinstance(value, bool):
+        _fail(path, "an integer", value)
+    return value
+
+
+def _decode_int_range(min: int, max: int) -> _Decoder[int]:
+    def decode(value: Any, path: _Path) -> int:
+        if not min <= _decode_int(value, path) <= max:
+            _fail(path, f"an integer between {min} and {max}", value)
+        return value
+
+    return decode
+
+
+def _decode_int_string(min: int, max: int) -> _Decoder[int]:
+    """64 bit integers are sent as strings, since a JSON number can not hold every value"""
+
+    def decode(value: Any, path: _Path) -> int:
+        if not isinstance(value, str) or not re.fullmatch(r"-?[0-9]+", value) or not min <= int(value) <= max:
+            _fail(path, f"a string holding an integer between {min} and {max}", value)
+        return int(value)
+
+    return decode
+
+
+def _decode_bytes(value: Any, path: _Path) -> bytes:
+    if isinstance(value, str):
+        try:
+            return base64.b64decode(value, validate=True)
+        except binascii.Error:
+            pass
+    _fail(path, "a base64 string", value)
+
+
+def _decode_timestamp(value: Any, path: _Path) -> datetime.datetime:
+    if isinstance(value, str):
+        try:
+            # fromisoformat only accepts a `Z` suffix from python 3.11
+            result = datetime.datetime.fromisoformat(re.sub(r"[Zz]$", "+00:00", value))
+            if result.tzinfo is not None:
+                return result
+        except ValueError:
+            pass
+    _fail(path, "an RFC 3339 timestamp", value)
+
+
+def _decode_date(value: Any, path: _Path) -> datetime.date:
+    if isinstance(value, str) and re.fullmatch(r"[0-9]{4}-[0-9]{2}-[0-9]{2}", value):
+        try:
+            return datetime.date.fromisoformat(value)
+        except ValueError:
+            pass
+    _fail(path, "a date", value)
+
+
+def _decode_duration(value: Any, path: _Path) -> datetime.timedelta:
+    return datetime.timedelta(seconds=_decode_float(value, path))
+
+
+def _decode_uuid(value: Any, path: _Path) -> uuid.UUID:
+    if isinstance(value, str):
+        try:
+            return uuid.UUID(value)
+        except ValueError:
+            pass
+    _fail(path, "a UUID", value)
+
+
+def _decode_decimal(value: Any, path: _Path) -> decimal.Decimal:
+    if isinstance(value, str) and re.fullmatch(r"-?[0-9]+(\.[0-9]+)?", value):
+        return decimal.Decimal(value)
+    _fail(path, "a decimal string", value)
+
+
+def _decode_float(value: Any, path: _Path) -> float:
+    if not isinstance(value, (int, float)) or isinstance(value, bool):
+        _fail(path, "a number", value)
+    return float(value)
+
+
+def _decode_bool(value: Any, path: _Path) -> bool:
+    if not isinstance(value, bool):
+        _fail(path, "a boolean", value)
+    return value
+
+
+def _decode_object(value: Any, path: _Path) -> Dict[str, Any]:
+    if not isinstance(value, dict):
+        _fail(path, "an object", value)
+    return value
+
+
+def _decode_field(obj: Dict[str, Any], path: _Path, key: str, decode: _Decoder[_T]) -> _T:
+    if key not in obj:
+        raise DecodeError(path, f"missing field {key!r}")
+    return decode(obj[key], (*path, key))
+
+
+def _decode_field_or(
+    obj: Dict[str, Any], path: _Path, key: str, decode: _Decoder[_T], default: Callable[[], _T]
+) -> _T:
+    if key not in obj:
+        return default()
+    return decode(obj[key], (*path, key))
+
+
+def _decode_enum(enum: Any, value: Any, path: _Path) -> Any:
+    if not isinstance(value, bool):
+        try:
+            return enum(value)
+        except ValueError:
+            pass
+    _fail(path, f"a member of {enum.__name__}", value)
+
+
+def _decode_nullable(inner: _Decoder[_T]) -> _Decoder[Optional[_T]]:
+    return lambda value, path: None if value is None else inner(value, path)
+
+
+def _decode_list(inner: _Decoder[_T]) -> _Decoder[List[_T]]:
+    def decode(value: Any, path: _Path) -> List[_T]:
+        if not isinstance(value, list):
+            _fail(path, "a list", value)
+        return [inner(item, (*path, i)) for i, item in enumerate(value)]
+
+    return decode
+
+
+def _decode_dict(inner: _Decoder[_T]) -> _Decoder[Dict[str, _T]]:
+    def decode(value: Any, path: _Path) -> Dict[str, _T]:
+        obj = _decode_object(value, path)
+        return {key: inner(item, (*path, key)) for key, item in obj.items()}
+
+    return decode
+
+
+def _decode_tuple(*items: _Decoder[Any]) -> _Decoder[Tuple[Any, ...]]:
+    def decode(value: Any, path: _Path) -> Tuple[Any, ...]:
+        if not isinstance(value, list) or len(value) != len(items):
+            _fail(path, f"a list of {len(items)} items", value)
+        return tuple(inner(item, (*path, i)) for i, (inner, item) in enumerate(zip(items, value)))
+
+    return decode
+
+
+def _encode_value(value: Any) -> Any:
+    return value
+
+
+def _encode_bytes(value: bytes) -> str:
+    return base64.b64encode(value).decode("ascii")
+
+
+def _encode_iso(value: Union[datetime.datetime, datetime.date]) -> str:
+    return value.isoformat()
+
+
+def _encode_duration(value: datetime.timedelta) -> float:
+    return value.total_seconds()
+
+
+def _encode_str(value: Any) -> str:
+    return str(value)
+
+
+def _encode_decimal(value: decimal.Decimal) -> str:
+    # str() may use an exponent, `1E+3`, which decimal strings do not allow
+    return format(value, "f")
+
+
+def _encode_nullable(inner: _Encoder) -> _Encoder:
+    return lambda value: None if value is None else inner(value)
+
+
+def _encode_list(inner: _Encoder) -> _Encoder:
+    return lambda value: [inner(item) for item in value]
+
+
+def _encode_dict(inner: _Encoder) -> _Encoder:
+    return lambda value: {key: inner(item) for key, item in value.items()}
+
+
+def _encode_tuple(*items: _Encoder) -> _Encoder:
+    return lambda value: [inner(item) for inner, item in zip(items, value)]
+
+@dataclass(kw_only=True)
+class Payment:
+    """A payment, with every well-known type"""
+    id: uuid.UUID
+    amount: decimal.Decimal
+    receipt: Optional[str]
+    paid_at: datetime.datetime
+    due_on: datetime.date
+    window: datetime.timedelta = datetime.timedelta(seconds=86400.0)
+    retry_after: datetime.timedelta = datetime.timedelta(seconds=0.5)
+    history: List[datetime.datetime]
+    notes: Optional[Dict[str, str]] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return encode_Payment(self)
+
+    @classmethod
+    def from_dict(cls, data: Any) -> Payment:
+        return decode_Payment(data)
+
+
+def decode_Payment(value: Any, path: _Path = ()) -> Payment:
+    obj = _decode_object(value, path)
+    return Payment(
+        id=_decode_field(obj, path, "id", _decode_uuid),
+        amount=_decode_field(obj, path, "amount", _decode_decimal),
+        receipt=_decode_field(obj, path, "receipt", _decode_nullable(_decode_str)),
+        paid_at=_decode_field(obj, path, "paidAt", _decode_timestamp),
+        due_on=_decode_field(obj, path, "dueOn", _decode_date),
+        window=_decode_field_or(obj, path, "window", _decode_duration, lambda: datetime.timedelta(seconds=86400.0)),
+        retry_after=_decode_field_or(obj, path, "retryAfter", _decode_duration, lambda: datetime.timedelta(seconds=0.5)),
+        history=_decode_field(obj, path, "history", _decode_list(_decode_timestamp)),
+        notes=_decode_field_or(obj, path, "notes", _decode_dict(_decode_str), lambda: None),
+    )
+
+
+def encode_Payment(value: Payment) -> Dict[str, Any]:
+    return {
+        "id": _encode_str(value.id),
+        "amount": _encode_decimal(value.amount),
+        "receipt": _encode_nullable(_encode_value)(value.receipt),
+        "paidAt": _encode_iso(value.paid_at),
+        "dueOn": _encode_iso(value.due_on),
+        "window": _encode_duration(value.window),
+        "retryAfter": _encode_duration(value.retry_after),
+        "history": _encode_list(_encode_iso)(value.history),
+        **({"notes": _encode_dict(_encode_value)(value.notes)} if value.notes is not None else {}),
+    }
+
+CHECKOUT: Final[str] = "https://example.com/checkout"
+
+HOLD: Final[datetime.timedelta] = datetime.timedelta(seconds=1.5)
\ No newline at end of file
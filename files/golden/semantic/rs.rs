@@ -0,0 +1,61 @@
+#![allow(non_snake_case)]
+#![allow(unused_imports)]
+#![allow(deprecated)]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A span of time, sent as a number of seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Duration(pub std::time::Duration);
+
+impl Serialize for Duration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0.as_secs_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        std::time::Duration::try_from_secs_f64(seconds)
+            .map(Duration)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A payment, with every well-known type
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Payment {
+    #[serde(rename = "id")]
+    pub id: uuid::Uuid,
+    #[serde(rename = "amount")]
+    pub amount: rust_decimal::Decimal,
+    #[serde(rename = "receipt")]
+    pub receipt: Option<String>,
+    #[serde(rename = "paidAt")]
+    pub paid_at: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "dueOn")]
+    pub due_on: chrono::NaiveDate,
+    #[serde(rename = "window", default = "default_Payment_window")]
+    pub window: Duration,
+    #[serde(rename = "retryAfter", default = "default_Payment_retry_after")]
+    pub retry_after: Duration,
+    #[serde(rename = "history")]
+    pub history: Vec<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "notes", skip_serializing_if = "Option::is_none")]
+    pub notes: Option<HashMap<String, String>>,
+}
+
+fn default_Payment_window() -> Duration {
+    Duration(std::time::Duration::from_nanos((86400.0 * 1e9) as u64))
+}
+
+fn default_Payment_retry_after() -> Duration {
+    Duration(std::time::Duration::from_nanos((0.5 * 1e9) as u64))
+}
+
+pub const CHECKOUT: &str = "https://example.com/checkout";
+
+pub const HOLD: Duration = Duration(std::time::Duration::from_nanos((1.5 * 1e9) as u64));
\ No newline at end of file
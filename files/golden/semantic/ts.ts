@@ -0,0 +1,28 @@
+type Optional<T> = T | null;
+
+/**
+ * A payment, with every well-known type
+ */
+export interface Payment {
+  id: string;
+  amount: string;
+  receipt: Optional<string>;
+  paidAt: Date;
+  dueOn: string;
+  window: number;
+  retryAfter: number;
+  history: Array<Date>;
+  notes?: Record<string, string>;
+}
+
+/** Returns the default values of the fields of `Payment` that have one */
+export function defaultsPayment(): Partial<Payment> {
+  return {
+    window: 86400.0,
+    retryAfter: 0.5,
+  };
+}
+
+export const CHECKOUT: string = "https://example.com/checkout";
+
+export const HOLD: number = 1.5;
\ No newline at end of file
@@ -21,10 +21,10 @@ fn get_test_program() -> Program {
 fn main() {
     let program = get_test_program();
 
-    glang::render_template(CODE, &program, std::io::stdout());
+    glang::render_template(CODE, &program, None, std::io::stdout());
 }
 
-static CODE: &'static str = r#"
+static CODE: &str = r#"
 #types
 
 string { string }
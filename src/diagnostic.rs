@@ -0,0 +1,45 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::parser::ParseError;
+
+/// Renders `error` rustc-style, i.e along with the offending line of `source` and a
+/// caret underline marking the exact location
+///
+/// ```text
+/// error: Unknown Type "Rolee"
+///   --> files/user.fr:27:13
+///    |
+/// 27 |     roles: [Rolee]
+///    |             ^^^^^
+/// ```
+pub fn render(path: &Path, source: &str, error: &ParseError) -> String {
+    let span = error.span;
+    let line_no = span.line.max(1) as usize;
+    let column = span.column.max(1) as usize;
+
+    let line = source.lines().nth(line_no - 1).unwrap_or("");
+
+    let gutter = line_no.to_string().len();
+    let pad = " ".repeat(gutter);
+
+    // Keep tabs in the prefix so that the carets line up with the source line
+    let prefix = line
+        .chars()
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+
+    let available = line.chars().count().saturating_sub(column - 1);
+    let width = ((span.end - span.start).max(1) as usize).min(available.max(1));
+
+    let mut out = String::new();
+
+    let _ = writeln!(out, "error: {}", error);
+    let _ = writeln!(out, "{}--> {}:{}:{}", pad, path.display(), line_no, column);
+    let _ = writeln!(out, "{} |", pad);
+    let _ = writeln!(out, "{} | {}", line_no, line);
+    let _ = writeln!(out, "{} | {}{}", pad, prefix, "^".repeat(width));
+
+    out
+}
@@ -0,0 +1,122 @@
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::Path;
+
+use crate::items::Program;
+use crate::language::Language;
+
+use crate::glang::render_template;
+
+/// Reserved words of Go, which can not name a package
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+pub struct GoGenerator {
+    /// Package of the generated files, or None to name it after the output directory
+    package: Option<String>,
+}
+
+impl GoGenerator {
+    pub fn new() -> Self {
+        Self { package: None }
+    }
+
+    /// Creates a generator that puts every file in the package `package`, or returns
+    /// None if it is not a valid package name
+    pub fn with_package(package: &str) -> Option<Self> {
+        Self::is_package_name(package).then(|| Self {
+            package: Some(package.to_string()),
+        })
+    }
+
+    fn is_package_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !KEYWORDS.contains(&name)
+    }
+
+    /// Go expects every file in a directory to belong to the same package, which is
+    /// usually named after the directory
+    fn package_name(outfile: &Path) -> String {
+        let name = outfile
+            .parent()
+            .and_then(|dir| dir.canonicalize().ok())
+            .and_then(|dir| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().to_lowercase())
+            })
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect::<String>();
+
+        // e.g the `go` directory that `--lang-dir` creates
+        if Self::is_package_name(&name) {
+            name
+        } else {
+            "schema".to_string()
+        }
+    }
+}
+
+impl Default for GoGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Language for GoGenerator {
+    fn lang_id(&self) -> &'static str {
+        "go"
+    }
+
+    fn extension(&self) -> &'static str {
+        "go"
+    }
+
+    fn generate(&self, program: &Program, outfile: &Path) {
+        let mut dest = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(outfile)
+            .expect("Failed to open file");
+
+        let package = match &self.package {
+            Some(package) => package.clone(),
+            None => Self::package_name(outfile),
+        };
+
+        writeln!(dest, "package {}\n", package).unwrap();
+
+        render_template(include_str!("go.gx"), program, None, dest).unwrap();
+    }
+}
@@ -0,0 +1,598 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+use crate::items::{
+    find_attribute, Attribute, DefaultValue, EnumDefinition, EnumVariantValue, Item, PrimitiveType,
+    Program, StructField, TyKind, UnionDefinition, Value, UNION_TAG,
+};
+use crate::language::Language;
+
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A JSON value whose objects keep their keys in insertion order
+enum Json {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn str(value: &str) -> Self {
+        Json::Str(value.to_string())
+    }
+
+    fn object<const N: usize>(entries: [(&str, Json); N]) -> Self {
+        Json::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn write_str(out: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+        out.write_char('"')?;
+        for c in value.chars() {
+            match c {
+                '"' => out.write_str("\\\"")?,
+                '\\' => out.write_str("\\\\")?,
+                '\n' => out.write_str("\\n")?,
+                '\r' => out.write_str("\\r")?,
+                '\t' => out.write_str("\\t")?,
+                c if c.is_control() => write!(out, "\\u{:04x}", c as u32)?,
+                c => out.write_char(c)?,
+            }
+        }
+        out.write_char('"')
+    }
+
+    fn write_pretty(&self, out: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = |out: &mut fmt::Formatter<'_>, level: usize| write!(out, "{:1$}", "", level * 2);
+
+        match self {
+            Json::Null => out.write_str("null"),
+            Json::Bool(value) => write!(out, "{}", value),
+            Json::Int(value) => write!(out, "{}", value),
+            Json::Float(value) => write!(out, "{:?}", value),
+            Json::Str(value) => Json::write_str(out, value),
+            Json::Array(items) if items.is_empty() => out.write_str("[]"),
+            Json::Array(items) => {
+                out.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    pad(out, indent + 1)?;
+                    item.write_pretty(out, indent + 1)?;
+                    out.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                pad(out, indent)?;
+                out.write_char(']')
+            }
+            Json::Object(entries) if entries.is_empty() => out.write_str("{}"),
+            Json::Object(entries) => {
+                out.write_str("{\n")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    pad(out, indent + 1)?;
+                    Json::write_str(out, key)?;
+                    out.write_str(": ")?;
+                    value.write_pretty(out, indent + 1)?;
+                    out.write_str(if i + 1 < entries.len() { ",\n" } else { "\n" })?;
+                }
+                pad(out, indent)?;
+                out.write_char('}')
+            }
+        }
+    }
+}
+
+/// Adds the lines of a doc comment to `schema` as its description, and marks it
+/// deprecated if the `@deprecated` attribute is given
+fn described(schema: Json, doc: &[String], attributes: &[Attribute]) -> Json {
+    let Json::Object(mut entries) = schema else {
+        return schema;
+    };
+
+    if find_attribute(attributes, "deprecated").is_some() {
+        entries.insert(0, ("deprecated".to_string(), Json::Bool(true)));
+    }
+
+    if !doc.is_empty() {
+        entries.insert(0, ("description".to_string(), Json::Str(doc.join("\n"))));
+    }
+
+    Json::Object(entries)
+}
+
+/// An integer between `min` and `max`
+fn integer_schema(min: i128, max: i128) -> Json {
+    Json::object([
+        ("type", Json::str("integer")),
+        ("minimum", Json::Int(min)),
+        ("maximum", Json::Int(max)),
+    ])
+}
+
+fn primitive_schema(prim: &PrimitiveType) -> Json {
+    let typ = |name| Json::object([("type", Json::str(name))]);
+    let formatted =
+        |format| Json::object([("type", Json::str("string")), ("format", Json::str(format))]);
+
+    match prim {
+        PrimitiveType::String => typ("string"),
+        PrimitiveType::Int => integer_schema(i32::MIN.into(), i32::MAX.into()),
+        PrimitiveType::Float | PrimitiveType::F32 => typ("number"),
+        PrimitiveType::Bool => typ("boolean"),
+        PrimitiveType::I8 => integer_schema(i8::MIN.into(), i8::MAX.into()),
+        PrimitiveType::I16 => integer_schema(i16::MIN.into(), i16::MAX.into()),
+        PrimitiveType::U8 => integer_schema(0, u8::MAX.into()),
+        PrimitiveType::U16 => integer_schema(0, u16::MAX.into()),
+        PrimitiveType::U32 => integer_schema(0, u32::MAX.into()),
+        // 64 bit integers are sent as strings
+        PrimitiveType::I64 => Json::object([
+            ("type", Json::str("string")),
+            ("pattern", Json::str("^-?[0-9]+$")),
+        ]),
+        PrimitiveType::U64 => Json::object([
+            ("type", Json::str("string")),
+            ("pattern", Json::str("^[0-9]+$")),
+        ]),
+        PrimitiveType::Bytes => Json::object([
+            ("type", Json::str("string")),
+            ("contentEncoding", Json::str("base64")),
+        ]),
+        PrimitiveType::Timestamp => formatted("date-time"),
+        PrimitiveType::Date => formatted("date"),
+        PrimitiveType::Duration => typ("number"),
+        PrimitiveType::Uuid => formatted("uuid"),
+        PrimitiveType::Decimal => Json::object([
+            ("type", Json::str("string")),
+            ("pattern", Json::str("^-?[0-9]+(\\.[0-9]+)?$")),
+        ]),
+        PrimitiveType::Url => formatted("uri"),
+    }
+}
+
+/// Appends the instances of generic types found in `ty` to `out`, outermost first
+fn collect_instances(ty: &TyKind, out: &mut Vec<TyKind>) {
+    match ty {
+        TyKind::Generic(_, args) => {
+            out.push(ty.clone());
+            for arg in args.iter() {
+                collect_instances(arg, out);
+            }
+        }
+        TyKind::Nullable(inner) | TyKind::Array(inner) | TyKind::Map(inner) => {
+            collect_instances(inner, out)
+        }
+        TyKind::Tuple(inner_tys) => {
+            for ty in inner_tys.iter() {
+                collect_instances(ty, out);
+            }
+        }
+        TyKind::Primitive(..) | TyKind::UserDefined(..) | TyKind::Param(..) => {}
+    }
+}
+
+/// Escapes a `$defs` key for use in the fragment of a `$ref`
+fn fragment_escape(key: &str) -> String {
+    let mut escaped = String::new();
+    for c in key.chars() {
+        match c {
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' => escaped.push(c),
+            c => {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    write!(escaped, "%{:02X}", byte).unwrap();
+                }
+            }
+        }
+    }
+    escaped
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_pretty(f, 0)
+    }
+}
+
+/* ==================================== */
+
+/// Builds the schemas of the types declared in a single program
+struct SchemaBuilder<'a> {
+    /// `$ref` of every type imported from other files
+    imported: HashMap<&'a str, String>,
+    /// Enums declared in the program, to write their variants in default values
+    enums: HashMap<&'a str, &'a EnumDefinition>,
+    /// Generic types declared in the program. A schema has no type parameters, so each
+    /// instance of these (`Page<Role>`) gets its own definition
+    generics: HashMap<&'a str, &'a Item>,
+}
+
+impl<'a> SchemaBuilder<'a> {
+    fn new(program: &'a Program, extension: &str) -> Self {
+        let imported = program
+            .imports
+            .iter()
+            .flat_map(|import| {
+                let file = format!("{}.{}", import.module, extension);
+                import
+                    .names
+                    .iter()
+                    .map(move |name| (name.as_str(), format!("{}#/$defs/{}", file, name)))
+            })
+            .collect();
+
+        let enums = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Enum(enum_) => Some((enum_.name.as_str(), enum_)),
+                _ => None,
+            })
+            .collect();
+
+        let generics = program
+            .items
+            .iter()
+            .filter(|item| !item_params(item).is_empty())
+            .map(|item| (item.name(), item))
+            .collect();
+
+        Self {
+            imported,
+            enums,
+            generics,
+        }
+    }
+
+    fn reference(&self, name: &str) -> Json {
+        let target = self
+            .imported
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| format!("#/$defs/{}", name));
+
+        Json::object([("$ref", Json::Str(target))])
+    }
+
+    fn type_schema(&self, ty: &TyKind) -> Json {
+        match ty {
+            TyKind::Primitive(prim) => primitive_schema(prim),
+            TyKind::UserDefined(name) => self.reference(name),
+            TyKind::Generic(name, _) if self.generics.contains_key(name.as_str()) => {
+                let target = format!("#/$defs/{}", fragment_escape(&ty.to_string()));
+                Json::object([("$ref", Json::Str(target))])
+            }
+            // Instances of imported generic types are not defined in any schema, and type
+            // parameters only appear in generic types, which are never written as is
+            TyKind::Generic(..) | TyKind::Param(..) => Json::Object(vec![]),
+            TyKind::Nullable(inner) => Json::object([(
+                "anyOf",
+                Json::Array(vec![
+                    self.type_schema(inner),
+                    Json::object([("type", Json::str("null"))]),
+                ]),
+            )]),
+            TyKind::Array(inner) => Json::object([
+                ("type", Json::str("array")),
+                ("items", self.type_schema(inner)),
+            ]),
+            TyKind::Map(inner) => Json::object([
+                ("type", Json::str("object")),
+                ("additionalProperties", self.type_schema(inner)),
+            ]),
+            TyKind::Tuple(inner_tys) => Json::object([
+                ("type", Json::str("array")),
+                (
+                    "prefixItems",
+                    Json::Array(inner_tys.iter().map(|ty| self.type_schema(ty)).collect()),
+                ),
+                ("items", Json::Bool(false)),
+                ("minItems", Json::Int(inner_tys.len() as i128)),
+            ]),
+        }
+    }
+
+    /// A value as it is sent on the wire, or None if it can not be known here (a variant
+    /// of an imported enum)
+    fn value_json(&self, ty: &TyKind, value: &Value) -> Option<Json> {
+        let inner = match ty {
+            TyKind::Nullable(inner) | TyKind::Array(inner) | TyKind::Map(inner) => inner.as_ref(),
+            ty => ty,
+        };
+
+        Some(match value {
+            Value::Null => Json::Null,
+            Value::Bool(val) => Json::Bool(*val),
+            Value::String(val) => Json::str(val),
+            Value::Int(val) => match inner {
+                TyKind::Primitive(PrimitiveType::I64 | PrimitiveType::U64) => {
+                    Json::Str(val.to_string())
+                }
+                _ => Json::Int(*val),
+            },
+            Value::Float(val) => Json::Float(*val),
+            Value::Array(items) => Json::Array(
+                items
+                    .iter()
+                    .map(|item| self.value_json(inner, item))
+                    .collect::<Option<_>>()?,
+            ),
+            Value::Tuple(items) => {
+                let tys = match inner {
+                    TyKind::Tuple(tys) => tys.as_slice(),
+                    _ => &[],
+                };
+                Json::Array(
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| self.value_json(tys.get(i).unwrap_or(inner), item))
+                        .collect::<Option<_>>()?,
+                )
+            }
+            Value::Map(entries) => Json::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| Some((key.clone(), self.value_json(inner, value)?)))
+                    .collect::<Option<_>>()?,
+            ),
+            Value::Variant { enum_name, variant } => {
+                let enum_ = self.enums.get(enum_name.as_str())?;
+                let variant = enum_.variants.iter().find(|v| v.name == *variant)?;
+                match &variant.value {
+                    EnumVariantValue::Int(val) => Json::Int((*val).into()),
+                    EnumVariantValue::String(val) => Json::str(val),
+                }
+            }
+        })
+    }
+
+    /// Adds the default value of a field to its schema
+    fn with_default(&self, schema: Json, default: Option<&DefaultValue>) -> Json {
+        let default = default.and_then(|default| self.value_json(&default.ty, &default.value));
+
+        match (schema, default) {
+            (Json::Object(mut entries), Some(default)) => {
+                entries.push(("default".to_string(), default));
+                Json::Object(entries)
+            }
+            (schema, _) => schema,
+        }
+    }
+
+    /// Schema of an object with the given fields. `tag` is an extra property fixed to
+    /// a constant value, used to discriminate union variants
+    fn object_schema<'f>(
+        &self,
+        fields: impl Iterator<Item = &'f StructField>,
+        tag: Option<&str>,
+    ) -> Json {
+        let mut properties = vec![];
+        let mut required = vec![];
+
+        if let Some(tag) = tag {
+            properties.push((
+                UNION_TAG.to_string(),
+                Json::object([("const", Json::str(tag))]),
+            ));
+            required.push(Json::str(UNION_TAG));
+        }
+
+        for field in fields {
+            properties.push((
+                field.wire_name().to_string(),
+                described(
+                    self.with_default(self.type_schema(&field.datatype), field.default.as_ref()),
+                    &field.doc,
+                    &field.attributes,
+                ),
+            ));
+
+            // Optional fields and fields with a default value can be left out. Nullable
+            // fields must still be present, their type alone allows them to be null
+            let can_be_absent = field.optional || field.default.is_some();
+            if !can_be_absent {
+                required.push(Json::str(field.wire_name()));
+            }
+        }
+
+        Json::object([
+            ("type", Json::str("object")),
+            ("properties", Json::Object(properties)),
+            ("required", Json::Array(required)),
+            ("additionalProperties", Json::Bool(false)),
+        ])
+    }
+
+    fn enum_schema(&self, enum_: &EnumDefinition) -> Json {
+        let values = enum_
+            .variants
+            .iter()
+            .map(|variant| match &variant.value {
+                EnumVariantValue::Int(val) => Json::Int((*val).into()),
+                EnumVariantValue::String(val) => Json::str(val),
+            })
+            .collect();
+
+        Json::object([
+            (
+                "type",
+                Json::str(if enum_.is_int_enum() {
+                    "integer"
+                } else {
+                    "string"
+                }),
+            ),
+            ("enum", Json::Array(values)),
+        ])
+    }
+
+    fn union_schema(&self, union_: &UnionDefinition) -> Json {
+        let variants = union_
+            .variants
+            .iter()
+            .map(|variant| {
+                described(
+                    self.object_schema(variant.fields.iter(), Some(&variant.name)),
+                    &variant.doc,
+                    &variant.attributes,
+                )
+            })
+            .collect();
+
+        Json::object([("oneOf", Json::Array(variants))])
+    }
+
+    fn item_schema(&self, item: &Item) -> Option<Json> {
+        let (schema, doc, attributes) = match item {
+            Item::Struct(struct_) => (
+                self.object_schema(struct_.all_fields(), None),
+                &struct_.doc,
+                &struct_.attributes,
+            ),
+            Item::Enum(enum_) => (self.enum_schema(enum_), &enum_.doc, &enum_.attributes),
+            Item::TypeAlias(alias) => (self.type_schema(&alias.typ), &alias.doc, &alias.attributes),
+            Item::Union(union_) => (self.union_schema(union_), &union_.doc, &union_.attributes),
+            // Services and constants are not types and have no representation in a schema
+            Item::Service(..) | Item::Constant(..) => return None,
+        };
+
+        Some(described(schema, doc, attributes))
+    }
+
+    /// Schema of the generic `item` given the type arguments `args`, along with the types
+    /// it references
+    fn instance_schema(&self, item: &Item, args: &[TyKind]) -> Option<(Json, Vec<TyKind>)> {
+        let params = item_params(item);
+
+        match item {
+            Item::Struct(struct_) => {
+                let fields = struct_
+                    .all_fields()
+                    .map(|field| StructField {
+                        datatype: field.datatype.substitute(params, args),
+                        ..field.clone()
+                    })
+                    .collect::<Vec<_>>();
+
+                let schema = described(
+                    self.object_schema(fields.iter(), None),
+                    &struct_.doc,
+                    &struct_.attributes,
+                );
+                let types = fields.into_iter().map(|field| field.datatype).collect();
+
+                Some((schema, types))
+            }
+            Item::TypeAlias(alias) => {
+                let typ = alias.typ.substitute(params, args);
+                let schema = described(self.type_schema(&typ), &alias.doc, &alias.attributes);
+
+                Some((schema, vec![typ]))
+            }
+            _ => None,
+        }
+    }
+
+    fn document(&self, program: &Program) -> Json {
+        let mut defs = vec![];
+        let mut instances = vec![];
+
+        for item in program.items.iter() {
+            if !item_params(item).is_empty() {
+                continue;
+            }
+
+            for ty in item.types() {
+                collect_instances(ty, &mut instances);
+            }
+
+            if let Some(schema) = self.item_schema(item) {
+                defs.push((item.name().to_string(), schema));
+            }
+        }
+
+        // Instances are written once each, including the ones only used by other
+        // instances
+        let mut written = HashSet::new();
+        let mut next = 0;
+
+        while let Some(instance) = instances.get(next).cloned() {
+            next += 1;
+
+            let TyKind::Generic(name, args) = &instance else {
+                continue;
+            };
+
+            let key = instance.to_string();
+            let Some(item) = self.generics.get(name.as_str()) else {
+                continue;
+            };
+
+            if !written.insert(key.clone()) {
+                continue;
+            }
+
+            if let Some((schema, types)) = self.instance_schema(item, args) {
+                for ty in types.iter() {
+                    collect_instances(ty, &mut instances);
+                }
+                defs.push((key, schema));
+            }
+        }
+
+        Json::object([
+            ("$schema", Json::str(SCHEMA_DIALECT)),
+            ("$defs", Json::Object(defs)),
+        ])
+    }
+}
+
+/// Type parameters of a generic item
+fn item_params(item: &Item) -> &[String] {
+    match item {
+        Item::Struct(struct_) => &struct_.params,
+        Item::TypeAlias(alias) => &alias.params,
+        _ => &[],
+    }
+}
+
+/* ==================================== */
+
+pub struct JsonSchemaGenerator {
+    _phantom: (),
+}
+
+impl JsonSchemaGenerator {
+    pub fn new() -> Self {
+        Self { _phantom: () }
+    }
+}
+
+impl Default for JsonSchemaGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Language for JsonSchemaGenerator {
+    fn lang_id(&self) -> &'static str {
+        "jsonschema"
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn generate(&self, program: &Program, outfile: &Path) {
+        let document = SchemaBuilder::new(program, self.extension()).document(program);
+
+        fs::write(outfile, format!("{}\n", document)).expect("Failed to write file");
+    }
+}
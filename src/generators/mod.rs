@@ -1,5 +1,13 @@
+mod go;
+mod jsonschema;
 mod python;
+mod rust;
+mod template;
 mod typescript;
 
+pub use go::GoGenerator;
+pub use jsonschema::JsonSchemaGenerator;
 pub use python::PythonGenerator;
+pub use rust::RustGenerator;
+pub use template::{LoadTemplateError, TemplateGenerator};
 pub use typescript::TypeScriptGenerator;
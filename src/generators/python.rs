@@ -3,7 +3,7 @@ use std::io::Write as _;
 use std::path::Path;
 
 use crate::items::Program;
-use crate::language::Language;
+use crate::language::{identifier, Language};
 
 use crate::glang::render_template;
 
@@ -17,6 +17,12 @@ impl PythonGenerator {
     }
 }
 
+impl Default for PythonGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Language for PythonGenerator {
     fn lang_id(&self) -> &'static str {
         "py"
@@ -26,6 +32,10 @@ impl Language for PythonGenerator {
         "py"
     }
 
+    fn module_name(&self, stem: &str) -> String {
+        identifier(stem)
+    }
+
     fn generate(&self, program: &Program, outfile: &Path) {
         let dest = OpenOptions::new()
             .write(true)
@@ -34,6 +44,6 @@ impl Language for PythonGenerator {
             .open(outfile)
             .expect("Failed to open file");
 
-        render_template(include_str!("python.gx"), program, dest).unwrap();
+        render_template(include_str!("python.gx"), program, None, dest).unwrap();
     }
 }
@@ -0,0 +1,49 @@
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::Path;
+
+use crate::items::Program;
+use crate::language::{identifier, Language};
+
+use crate::glang::render_template;
+
+pub struct RustGenerator {
+    _phantom: (),
+}
+
+impl RustGenerator {
+    pub fn new() -> Self {
+        Self { _phantom: () }
+    }
+}
+
+impl Default for RustGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Language for RustGenerator {
+    fn lang_id(&self) -> &'static str {
+        "rs"
+    }
+
+    fn extension(&self) -> &'static str {
+        "rs"
+    }
+
+    fn module_name(&self, stem: &str) -> String {
+        identifier(stem)
+    }
+
+    fn generate(&self, program: &Program, outfile: &Path) {
+        let dest = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(outfile)
+            .expect("Failed to open file");
+
+        render_template(include_str!("rust.gx"), program, None, dest).unwrap();
+    }
+}
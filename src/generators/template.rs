@@ -0,0 +1,81 @@
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::items::Program;
+use crate::language::Language;
+
+use crate::glang::{compile_template, render_template, TemplateError};
+
+#[derive(Error, Debug)]
+pub enum LoadTemplateError {
+    #[error("Failed to read \"{path}\": {source}")]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error("{}:{error}", .path.display())]
+    Invalid { path: PathBuf, error: TemplateError },
+}
+
+/// Renders a user supplied `.gx` template, e.g to target a language (or a house style)
+/// that is not shipped with forser
+pub struct TemplateGenerator {
+    lang_id: String,
+    extension: String,
+    source: String,
+}
+
+impl TemplateGenerator {
+    pub fn new(lang_id: String, extension: String, source: String) -> Self {
+        Self {
+            lang_id,
+            extension,
+            source,
+        }
+    }
+
+    /// Loads and validates the template at `path`. The generator is identified by the
+    /// template's file name
+    pub fn from_file<P: AsRef<Path>>(path: P, extension: &str) -> Result<Self, LoadTemplateError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| LoadTemplateError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        if let Err(error) = compile_template(&source) {
+            return Err(LoadTemplateError::Invalid {
+                path: path.to_path_buf(),
+                error,
+            });
+        }
+        let lang_id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| extension.to_string());
+
+        Ok(Self::new(lang_id, extension.to_string(), source))
+    }
+}
+
+impl Language for TemplateGenerator {
+    fn lang_id(&self) -> &str {
+        &self.lang_id
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn generate(&self, program: &Program, outfile: &Path) {
+        let dest = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(outfile)
+            .expect("Failed to open file");
+
+        render_template(&self.source, program, None, dest).unwrap();
+    }
+}
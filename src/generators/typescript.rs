@@ -8,18 +8,33 @@ use crate::language::Language;
 use crate::glang::render_template;
 
 pub struct TypeScriptGenerator {
-    _phantom: (),
+    /// Also emit a `decodeX(value: unknown)` function for every type
+    decoders: bool,
 }
 
 impl TypeScriptGenerator {
     pub fn new() -> Self {
-        Self { _phantom: () }
+        Self { decoders: false }
+    }
+
+    pub fn with_decoders() -> Self {
+        Self { decoders: true }
+    }
+}
+
+impl Default for TypeScriptGenerator {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Language for TypeScriptGenerator {
     fn lang_id(&self) -> &'static str {
-        "ts"
+        if self.decoders {
+            "ts-decoders"
+        } else {
+            "ts"
+        }
     }
 
     fn extension(&self) -> &'static str {
@@ -34,6 +49,8 @@ impl Language for TypeScriptGenerator {
             .open(outfile)
             .expect("Failed to open file");
 
-        render_template(include_str!("typescript.gx"), program, dest).unwrap();
+        let flavor = self.decoders.then_some("decoders");
+
+        render_template(include_str!("typescript.gx"), program, flavor, dest).unwrap();
     }
 }
@@ -34,13 +34,13 @@ impl<'a, W> std::ops::Deref for SpanWriter<'a, W> {
     type Target = W;
 
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        self.inner
     }
 }
 
 impl<'a, W> std::ops::DerefMut for SpanWriter<'a, W> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+        self.inner
     }
 }
 
@@ -74,8 +74,8 @@ where
         func(item, &mut writer)?;
     }
 
-    if opts.trailing && opts.delimeter.is_some() {
-        writer.write_str(opts.delimeter.as_ref().unwrap())?;
+    if let (true, Some(delim)) = (opts.trailing, &opts.delimeter) {
+        writer.write_str(delim.as_str())?;
     }
 
     Ok(())
@@ -89,28 +89,96 @@ pub fn render_span<W: Write>(
     indent: u16,
     template: &Template,
 ) -> io::Result<()> {
-    let mut writer = SpanWriter::new(dest);
+    scope.check_complete();
 
+    let mut writer = SpanWriter::new(dest);
     let mut current_line_indent = 0;
 
-    for inst in span.instructions.iter() {
+    render_instructions(
+        &span.instructions,
+        &mut writer,
+        &mut scope,
+        indent,
+        &mut current_line_indent,
+        template,
+    )
+}
+
+/// Variables and flags used inside a `%for%` loop are prefixed with the name of the loop
+/// item, e.g `%f.name%`. The item's own scope is current at that point, so the prefix
+/// is dropped
+fn unprefixed(name: &str) -> &str {
+    name.split_once('.').map_or(name, |(_, name)| name)
+}
+
+fn render_instructions<W: Write>(
+    instructions: &[Instruction],
+    writer: &mut SpanWriter<W>,
+    scope: &mut Scope<W>,
+    indent: u16,
+    current_line_indent: &mut u16,
+    template: &Template,
+) -> io::Result<()> {
+    for inst in instructions.iter() {
         match inst {
             Instruction::Newline => {
                 writer.write_char('\n')?;
-                current_line_indent = 0;
+                *current_line_indent = 0;
                 writer.do_indent(indent)?;
             }
             Instruction::Indent(size) => {
                 let size = *size;
-                current_line_indent = size;
+                *current_line_indent = size;
                 writer.do_indent(size)?;
             }
             Instruction::Literal(val) => {
                 writer.write_str(val)?;
             }
             Instruction::Expand { var, opts } => {
-                let expander = scope.get_expander(var);
-                expander.expand(&mut writer, indent + current_line_indent, opts, template)?;
+                let expander = scope.get_expander(unprefixed(var));
+                expander.expand(writer, indent + *current_line_indent, opts, template)?;
+            }
+            Instruction::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let branch = if scope.get_flag(unprefixed(cond.flag)) != cond.negated {
+                    then
+                } else {
+                    otherwise
+                };
+
+                render_instructions(
+                    &branch.instructions,
+                    writer,
+                    scope,
+                    indent,
+                    current_line_indent,
+                    template,
+                )?;
+            }
+            Instruction::For {
+                var,
+                opts,
+                body,
+                block,
+                ..
+            } => {
+                let opts = ExpandOptions {
+                    body: Some(body),
+                    ..opts.clone()
+                };
+
+                // A loop on its own line carries the indentation of its body as written
+                let base = if *block {
+                    indent
+                } else {
+                    indent + *current_line_indent
+                };
+
+                let expander = scope.get_expander(unprefixed(var));
+                expander.expand(writer, base, &opts, template)?;
             }
         }
     }
@@ -0,0 +1,96 @@
+use std::io;
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TemplateErrorKind {
+    #[error("Unknown Section \"{0}\"")]
+    UnknownSection(String),
+
+    #[error("Section \"{0}\" is already defined")]
+    RedefinedSection(String),
+
+    #[error("Section \"{name}\" starts inside section \"{parent}\"")]
+    NestedSection { name: String, parent: String },
+
+    #[error("\"#end/{0}\" does not close any section")]
+    UnopenedSection(String),
+
+    #[error("Expected \"#end/{expected}\", found \"#end/{found}\"")]
+    MismatchedEnd { expected: String, found: String },
+
+    #[error("Section \"{0}\" is never closed")]
+    UnclosedSection(String),
+
+    #[error("Expected \"{{\" after type \"{0}\"")]
+    MissingTypeBody(String),
+
+    #[error("Unclosed \"{{\" in type \"{0}\"")]
+    UnclosedTypeBody(String),
+
+    #[error("Unknown Type \"{0}\"")]
+    UnknownType(String),
+
+    #[error("Unknown Value \"{0}\"")]
+    UnknownValue(String),
+
+    #[error("Unknown Variable \"%{var}%\" in section \"{section}\"")]
+    UnknownVariable { var: String, section: String },
+
+    #[error("Variable \"%{0}%\" can not be rendered with a visitor")]
+    UnexpectedVisitor(String),
+
+    #[error("Unknown Filter \"{0}\"")]
+    UnknownFilter(String),
+
+    #[error("Unknown Flag \"{flag}\" in section \"{section}\"")]
+    UnknownFlag { flag: String, section: String },
+
+    #[error("Variable \"%{0}%\" can not be looped over")]
+    NotIterable(String),
+
+    #[error("Expected \"%for item in variable%\", found \"%{0}%\"")]
+    MalformedLoop(String),
+
+    #[error("\"%{0}%\" does not belong to any block")]
+    UnexpectedTag(String),
+
+    #[error("Block \"%{0}%\" is never closed")]
+    UnclosedBlock(String),
+}
+
+/// An error in a template, located by the 1-based line and column it occurs at
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{line}:{column}: {kind}")]
+pub struct TemplateError {
+    pub kind: TemplateErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl TemplateError {
+    /// Creates an error located at `part`, which must be a slice of `source`
+    pub(super) fn at(source: &str, part: &str, kind: TemplateErrorKind) -> Self {
+        let offset = (part.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+        let offset = if source.is_char_boundary(offset) {
+            offset
+        } else {
+            0
+        };
+
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+
+        Self { kind, line, column }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RenderError {
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
@@ -2,9 +2,12 @@ use super::template::{ExpandOptions, Template};
 use std::io::{self, Write};
 
 pub mod msg_enum;
+pub mod msg_service;
 pub mod msg_struct;
+pub mod msg_union;
 pub mod text;
 pub mod typ;
+pub mod value;
 
 pub trait Expander<W> {
     fn expand(
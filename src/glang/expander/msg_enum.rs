@@ -9,13 +9,15 @@ use crate::glang::scope::Scope;
 use crate::glang::template::{ExpandOptions, Template};
 
 pub struct EnumVariantsExpander<'a, F> {
+    enum_name: &'a str,
     variants: F,
     _phantom: PhantomData<&'a ()>,
 }
 
 impl<'a, F> EnumVariantsExpander<'a, F> {
-    pub fn new(variants: F) -> Self {
+    pub fn new(enum_name: &'a str, variants: F) -> Self {
         Self {
+            enum_name,
             variants,
             _phantom: PhantomData,
         }
@@ -47,11 +49,18 @@ where
                 };
 
                 render_span(
-                    &template.enum_variant,
+                    template.visitor("enum_variant_visitor", &template.enum_variant, opts),
                     dest,
-                    Scope::new()
+                    Scope::new("enum_variant_visitor")
                         .add_text("name", &variant.name)
-                        .add_text("val", &value),
+                        .add_doc(&variant.doc)
+                        .add_attributes(&variant.attributes)
+                        .add_text("enum", self.enum_name)
+                        .add_text("val", &value)
+                        .add_flag(
+                            "is_int_enum",
+                            matches!(variant.value, EnumVariantValue::Int(..)),
+                        ),
                     // .add_expander("ty", FieldTypeExpander(&field.datatype)),
                     indent,
                     template,
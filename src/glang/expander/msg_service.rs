@@ -0,0 +1,54 @@
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+use crate::items::ServiceMethod;
+
+use crate::glang::emit::{newline_delimeters, render_span};
+use crate::glang::expander::Expander;
+use crate::glang::scope::Scope;
+use crate::glang::template::{ExpandOptions, Template};
+
+use super::typ::TypeExpander;
+
+pub struct ServiceMethodsExpander<'a, F> {
+    methods: F,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a, F> ServiceMethodsExpander<'a, F> {
+    pub fn new(methods: F) -> Self {
+        Self {
+            methods,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, F, W> Expander<W> for ServiceMethodsExpander<'a, F>
+where
+    W: Write,
+    F: Iterator<Item = &'a ServiceMethod> + Clone,
+{
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        newline_delimeters(dest, self.methods.clone(), opts, indent, |method, dest| {
+            render_span(
+                template.visitor("method_visitor", &template.method_body, opts),
+                dest,
+                Scope::new("method_visitor")
+                    .add_text("name", &method.name)
+                    .add_doc(&method.doc)
+                    .add_attributes(&method.attributes)
+                    .add_expander("input", TypeExpander::new(&method.input))
+                    .add_expander("output", TypeExpander::new(&method.output)),
+                indent,
+                template,
+            )
+        })
+    }
+}
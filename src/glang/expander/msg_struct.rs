@@ -1,25 +1,45 @@
 use std::io::{self, Write};
-use std::marker::PhantomData;
 
-use crate::items::StructField;
+use crate::items::{StructField, TyKind, Value};
 
 use crate::glang::emit::{newline_delimeters, render_span};
 use crate::glang::expander::Expander;
 use crate::glang::scope::Scope;
 use crate::glang::template::{ExpandOptions, Template};
 
+use super::text::TextListExpander;
 use super::typ::TypeExpander;
+use super::value::DefaultExpander;
+
+/// What fields belong to: a struct, or a variant of a union
+#[derive(Clone)]
+pub struct Owner<'a> {
+    /// Name of the struct, or of the union followed by the name of the variant
+    pub name: String,
+    /// Type parameters of the struct
+    pub params: &'a [String],
+}
 
 pub struct StructFieldsExpander<'a, F> {
+    owner: Owner<'a>,
     fields: F,
-    _phantom: PhantomData<&'a ()>,
+    /// Only write the fields that have a default value
+    defaults_only: bool,
 }
 
 impl<'a, F> StructFieldsExpander<'a, F> {
-    pub fn new(fields: F) -> Self {
+    pub fn new(owner: Owner<'a>, fields: F) -> Self {
         Self {
+            owner,
             fields,
-            _phantom: PhantomData,
+            defaults_only: false,
+        }
+    }
+
+    pub fn with_defaults_only(self) -> Self {
+        Self {
+            defaults_only: true,
+            ..self
         }
     }
 }
@@ -36,16 +56,69 @@ where
         opts: &ExpandOptions,
         template: &Template<'_>,
     ) -> io::Result<()> {
-        newline_delimeters(dest, self.fields.clone(), opts, indent, |field, dest| {
+        let defaults_only = self.defaults_only;
+        let fields = self
+            .fields
+            .clone()
+            .filter(|field| !defaults_only || field.default.is_some());
+
+        newline_delimeters(dest, fields, opts, indent, |field, dest| {
+            // Optional fields are written as nullable in languages that can not tell an
+            // absent value from a null one
+            let nullable_ty = match &field.datatype {
+                TyKind::Nullable(..) => field.datatype.clone(),
+                ty => TyKind::Nullable(Box::new(ty.clone())),
+            };
+
             render_span(
-                &template.field_body,
+                template.visitor("field_visitor", &template.field_body, opts),
                 dest,
-                Scope::new()
+                Scope::new("field_visitor")
                     .add_text("name", &field.name)
-                    .add_expander("ty", TypeExpander(&field.datatype)),
+                    .add_text("wire", field.wire_name())
+                    .add_doc(&field.doc)
+                    .add_attributes(&field.attributes)
+                    .add_flag(
+                        "is_nullable",
+                        matches!(field.datatype, TyKind::Nullable(..)),
+                    )
+                    .add_flag("is_optional", field.optional)
+                    .add_flag("has_default", field.default.is_some())
+                    .add_flag(
+                        "has_mutable_default",
+                        field.default.as_ref().is_some_and(|default| {
+                            matches!(default.value, Value::Array(..) | Value::Map(..))
+                        }),
+                    )
+                    .add_expander("ty", TypeExpander::new(&field.datatype))
+                    .add_expander("nullable_ty", TypeExpander::new(&nullable_ty))
+                    .add_expander("default", DefaultExpander(field.default.as_ref()))
+                    .add_text("owner", &self.owner.name)
+                    .add_expander("owner_params", TextListExpander(self.owner.params))
+                    .add_flag("has_owner_params", !self.owner.params.is_empty()),
                 indent,
                 template,
             )
         })
     }
 }
+
+/* --------- */
+
+/// Writes the type a struct extends, or nothing if it extends none
+pub struct BaseExpander<'a>(/* base: */ pub Option<&'a TyKind>);
+
+impl<'a, W: Write> Expander<W> for BaseExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        match self.0 {
+            Some(base) => TypeExpander::new(base).expand(dest, indent, opts, template),
+            None => Ok(()),
+        }
+    }
+}
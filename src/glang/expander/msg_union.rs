@@ -0,0 +1,130 @@
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+use crate::items::{UnionVariant, UNION_TAG};
+
+use crate::glang::emit::{newline_delimeters, render_span};
+use crate::glang::expander::Expander;
+use crate::glang::scope::Scope;
+use crate::glang::template::{ExpandOptions, Template};
+
+use super::msg_struct::{Owner, StructFieldsExpander};
+
+pub struct UnionVariantsExpander<'a, F> {
+    union_name: &'a str,
+    variants: F,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a, F> UnionVariantsExpander<'a, F> {
+    pub fn new(union_name: &'a str, variants: F) -> Self {
+        Self {
+            union_name,
+            variants,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, F, W> Expander<W> for UnionVariantsExpander<'a, F>
+where
+    W: Write,
+    F: Iterator<Item = &'a UnionVariant> + Clone,
+{
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        newline_delimeters(
+            dest,
+            self.variants.clone(),
+            opts,
+            indent,
+            |variant, dest| {
+                let owner = Owner {
+                    name: format!("{}{}", self.union_name, variant.name),
+                    params: &[],
+                };
+
+                render_span(
+                    template.visitor("union_variant_visitor", &template.union_variant, opts),
+                    dest,
+                    Scope::new("union_variant_visitor")
+                        .add_text("name", &variant.name)
+                        .add_doc(&variant.doc)
+                        .add_attributes(&variant.attributes)
+                        .add_text("union", self.union_name)
+                        .add_text("tag", UNION_TAG)
+                        .add_flag("has_fields", !variant.fields.is_empty())
+                        .add_flag(
+                            "has_defaults",
+                            variant.fields.iter().any(|field| field.default.is_some()),
+                        )
+                        .add_flag(
+                            "has_optional_fields",
+                            variant.fields.iter().any(|field| field.optional),
+                        )
+                        .add_expander(
+                            "fields",
+                            StructFieldsExpander::new(owner.clone(), variant.fields.iter()),
+                        )
+                        .add_expander(
+                            "defaults",
+                            StructFieldsExpander::new(owner, variant.fields.iter())
+                                .with_defaults_only(),
+                        ),
+                    indent,
+                    template,
+                )
+            },
+        )
+    }
+}
+
+/* --------- */
+
+/// Writes the fields of every variant that have a default value
+pub struct UnionDefaultsExpander<'a> {
+    union_name: &'a str,
+    variants: &'a [UnionVariant],
+}
+
+impl<'a> UnionDefaultsExpander<'a> {
+    pub fn new(union_name: &'a str, variants: &'a [UnionVariant]) -> Self {
+        Self {
+            union_name,
+            variants,
+        }
+    }
+}
+
+impl<'a, W: Write> Expander<W> for UnionDefaultsExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        let fields = self.variants.iter().flat_map(|variant| {
+            let defaults = variant
+                .fields
+                .iter()
+                .filter(|field| field.default.is_some());
+            defaults.map(move |field| (variant, field))
+        });
+
+        newline_delimeters(dest, fields, opts, indent, |(variant, field), dest| {
+            let owner = Owner {
+                name: format!("{}{}", self.union_name, variant.name),
+                params: &[],
+            };
+
+            StructFieldsExpander::new(owner, std::iter::once(field))
+                .expand(dest, indent, opts, template)
+        })
+    }
+}
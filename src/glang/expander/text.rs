@@ -1,7 +1,11 @@
 use super::Expander;
 use std::io::{self, Write};
 
+use crate::glang::emit::{newline_delimeters, render_span};
+use crate::glang::filter::apply_filter;
+use crate::glang::scope::Scope;
 use crate::glang::template::{ExpandOptions, Template};
+use crate::items::Attribute;
 
 pub struct TextExpander<'a>(/* text: */ pub &'a str);
 
@@ -10,9 +14,60 @@ impl<'a, W: Write> Expander<W> for TextExpander<'a> {
         &mut self,
         dest: &mut W,
         _indent: u16,
-        _opts: &ExpandOptions,
-        _template: &Template<'_>,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
     ) -> io::Result<()> {
-        write!(dest, "{}", self.0)
+        match &opts.filter {
+            Some(filter) => write!(dest, "{}", apply_filter(filter, self.0, &template.keywords)),
+            None => write!(dest, "{}", self.0),
+        }
+    }
+}
+
+/* --------- */
+
+/// Expands to the first argument of an attribute, or nothing if the attribute is absent
+/// or has no arguments
+pub struct AttributeExpander<'a>(/* attribute: */ pub Option<&'a Attribute>);
+
+impl<'a, W: Write> Expander<W> for AttributeExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        let value = self
+            .0
+            .and_then(Attribute::value)
+            .map(ToString::to_string)
+            .unwrap_or_default();
+
+        TextExpander(&value).expand(dest, indent, opts, template)
+    }
+}
+
+/* --------- */
+
+pub struct TextListExpander<'a>(/* items: */ pub &'a [String]);
+
+impl<'a, W: Write> Expander<W> for TextListExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        newline_delimeters(dest, self.0.iter(), opts, indent, |item, dest| {
+            render_span(
+                template.visitor("text_visitor", &template.echo, opts),
+                dest,
+                Scope::new("text_visitor").add_text("value", item),
+                indent,
+                template,
+            )
+        })
     }
 }
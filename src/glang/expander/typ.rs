@@ -1,5 +1,4 @@
 use std::io::{self, Write};
-use std::marker::PhantomData;
 
 use crate::items::{PrimitiveType, TyKind};
 
@@ -10,15 +9,12 @@ use crate::glang::template::{ExpandOptions, Template};
 
 struct TupleTypeExpander<'a, F> {
     types: F,
-    _phantom: PhantomData<&'a ()>,
+    table: Option<&'a str>,
 }
 
 impl<'a, F> TupleTypeExpander<'a, F> {
-    pub fn new(types: F) -> Self {
-        Self {
-            types,
-            _phantom: PhantomData,
-        }
+    pub fn new(types: F, table: Option<&'a str>) -> Self {
+        Self { types, table }
     }
 }
 
@@ -38,7 +34,13 @@ where
             render_span(
                 &template.echo,
                 dest,
-                Scope::new().add_expander("value", TypeExpander(tykind)),
+                Scope::new("text_visitor").add_expander(
+                    "value",
+                    TypeExpander {
+                        ty: tykind,
+                        table: self.table,
+                    },
+                ),
                 indent,
                 template,
             )
@@ -48,67 +50,100 @@ where
 
 /* --------- */
 
-pub struct TypeExpander<'s>(pub &'s TyKind);
+/// Writes a type using one of the template's type tables. The table is selected with
+/// `%ty@name%` and is inherited by the inner types
+pub struct TypeExpander<'s> {
+    ty: &'s TyKind,
+    table: Option<&'s str>,
+}
+
+impl<'s> TypeExpander<'s> {
+    pub fn new(ty: &'s TyKind) -> Self {
+        Self { ty, table: None }
+    }
+}
 
 impl<'a, W: Write> Expander<W> for TypeExpander<'a> {
     fn expand(
         &mut self,
         dest: &mut W,
         indent: u16,
-        _opts: &ExpandOptions,
+        opts: &ExpandOptions,
         template: &Template<'_>,
     ) -> io::Result<()> {
-        match self.0 {
-            TyKind::Primitive(prim) => match prim {
-                PrimitiveType::String => {
-                    render_span(&template.field_string, dest, Scope::new(), indent, template)?;
-                }
-                PrimitiveType::Int => {
-                    render_span(&template.field_int, dest, Scope::new(), indent, template)?;
-                }
-                PrimitiveType::Float => {
-                    render_span(&template.field_float, dest, Scope::new(), indent, template)?;
-                }
-                PrimitiveType::Bool => {
-                    render_span(&template.field_bool, dest, Scope::new(), indent, template)?;
-                }
-            },
+        let table = opts.visitor.as_deref().or(self.table);
+        let types = template.types(table);
+
+        let inner = |ty| TypeExpander { ty, table };
+
+        match self.ty {
+            TyKind::Primitive(prim) => {
+                render_span(
+                    types.primitive(prim),
+                    dest,
+                    Scope::new(prim.name()),
+                    indent,
+                    template,
+                )?;
+            }
 
             TyKind::UserDefined(name) => {
                 render_span(
-                    &template.field_struct,
+                    &types.udt,
+                    dest,
+                    Scope::new("struct").add_text("T", name),
+                    indent,
+                    template,
+                )?;
+            }
+
+            TyKind::Generic(name, args) => {
+                render_span(
+                    &types.generic,
+                    dest,
+                    Scope::new("generic")
+                        .add_text("T", name)
+                        .add_expander("args", TupleTypeExpander::new(args.iter(), table)),
+                    indent,
+                    template,
+                )?;
+            }
+
+            TyKind::Param(name) => {
+                render_span(
+                    &types.param,
                     dest,
-                    Scope::new().add_text("T", &name),
+                    Scope::new("param").add_text("T", name),
                     indent,
                     template,
                 )?;
             }
 
-            TyKind::Nullable(inner) => {
+            TyKind::Nullable(ty) => {
                 render_span(
-                    &template.field_null,
+                    &types.null,
                     dest,
-                    Scope::new().add_expander("T", TypeExpander(inner.as_ref())),
+                    Scope::new("null").add_expander("T", inner(ty.as_ref())),
                     indent,
                     template,
                 )?;
             }
 
-            TyKind::Array(inner) => {
+            TyKind::Array(ty) => {
                 render_span(
-                    &template.field_array,
+                    &types.array,
                     dest,
-                    Scope::new().add_expander("T", TypeExpander(inner.as_ref())),
+                    Scope::new("array").add_expander("T", inner(ty.as_ref())),
                     indent,
                     template,
                 )?;
             }
 
-            TyKind::Map(inner) => {
+            TyKind::Map(ty) => {
                 render_span(
-                    &template.field_map,
+                    &types.map,
                     dest,
-                    Scope::new().add_expander("T", TypeExpander(inner.as_ref())),
+                    Scope::new("map").add_expander("T", inner(ty.as_ref())),
                     indent,
                     template,
                 )?;
@@ -116,9 +151,10 @@ impl<'a, W: Write> Expander<W> for TypeExpander<'a> {
 
             TyKind::Tuple(inner_tys) => {
                 render_span(
-                    &template.field_tuple,
+                    &types.tuple,
                     dest,
-                    Scope::new().add_expander("Ts", TupleTypeExpander::new(inner_tys.iter())),
+                    Scope::new("tuple")
+                        .add_expander("Ts", TupleTypeExpander::new(inner_tys.iter(), table)),
                     indent,
                     template,
                 )?;
@@ -0,0 +1,280 @@
+use std::io::{self, Write};
+
+use crate::items::{DefaultValue, PrimitiveType, TyKind, Value};
+
+use crate::glang::emit::{newline_delimeters, render_span};
+use crate::glang::expander::Expander;
+use crate::glang::scope::Scope;
+use crate::glang::template::{ExpandOptions, Template};
+
+use super::typ::TypeExpander;
+
+/// Writes the elements of an array value, each of type `ty`
+struct ValueListExpander<'a> {
+    ty: &'a TyKind,
+    values: &'a [Value],
+    table: Option<&'a str>,
+}
+
+impl<'a, W: Write> Expander<W> for ValueListExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        newline_delimeters(dest, self.values.iter(), opts, indent, |value, dest| {
+            render_span(
+                &template.echo,
+                dest,
+                Scope::new("text_visitor").add_expander(
+                    "value",
+                    ValueExpander::with_table(self.ty, value, self.table),
+                ),
+                indent,
+                template,
+            )
+        })
+    }
+}
+
+/* --------- */
+
+/// Writes the elements of a tuple value, each of the type at its position in `tys`
+struct TupleItemsExpander<'a> {
+    tys: &'a [TyKind],
+    /// The type of elements that are not in `tys`, when the value does not match its type
+    rest: &'a TyKind,
+    values: &'a [Value],
+    table: Option<&'a str>,
+}
+
+impl<'a, W: Write> Expander<W> for TupleItemsExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        let items = self.values.iter().enumerate();
+        newline_delimeters(dest, items, opts, indent, |(i, value), dest| {
+            let ty = self.tys.get(i).unwrap_or(self.rest);
+            render_span(
+                &template.echo,
+                dest,
+                Scope::new("text_visitor")
+                    .add_expander("value", ValueExpander::with_table(ty, value, self.table)),
+                indent,
+                template,
+            )
+        })
+    }
+}
+
+/* --------- */
+
+/// Writes the entries of a map value, each value being of type `ty`
+struct MapEntriesExpander<'a> {
+    ty: &'a TyKind,
+    entries: &'a [(String, Value)],
+    table: Option<&'a str>,
+}
+
+impl<'a, W: Write> Expander<W> for MapEntriesExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        newline_delimeters(
+            dest,
+            self.entries.iter(),
+            opts,
+            indent,
+            |(key, value), dest| {
+                render_span(
+                    &template.values(self.table).entry,
+                    dest,
+                    Scope::new("value_entry").add_text("key", key).add_expander(
+                        "value",
+                        ValueExpander::with_table(self.ty, value, self.table),
+                    ),
+                    indent,
+                    template,
+                )
+            },
+        )
+    }
+}
+
+/* --------- */
+
+/// Writes a value of type `ty` using one of the template's value tables. The table is
+/// selected with `%value@name%` and is inherited by the inner values
+pub struct ValueExpander<'a> {
+    ty: &'a TyKind,
+    value: &'a Value,
+    table: Option<&'a str>,
+}
+
+impl<'a> ValueExpander<'a> {
+    pub fn new(ty: &'a TyKind, value: &'a Value) -> Self {
+        Self::with_table(ty, value, None)
+    }
+
+    fn with_table(ty: &'a TyKind, value: &'a Value, table: Option<&'a str>) -> Self {
+        Self { ty, value, table }
+    }
+}
+
+impl<'a, W: Write> Expander<W> for ValueExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        let table = opts.visitor.as_deref().or(self.table);
+        let values = template.values(table);
+
+        // Types that do not match the value (such as imported type aliases, which are not
+        // resolved) are written according to the value alone
+        let inner = match self.ty {
+            TyKind::Array(inner) | TyKind::Map(inner) => inner.as_ref(),
+            ty => ty,
+        };
+
+        let (span, scope) = match (self.ty, self.value) {
+            (_, Value::Null) => (&values.null, Scope::new("value_const")),
+            (TyKind::Nullable(inner), value) => (
+                &values.some,
+                Scope::new("value_some")
+                    .add_expander("T", ValueExpander::with_table(inner, value, table))
+                    .add_expander("ty", TypeExpander::new(inner)),
+            ),
+            (_, Value::Bool(true)) => (&values.true_, Scope::new("value_const")),
+            (_, Value::Bool(false)) => (&values.false_, Scope::new("value_const")),
+            (ty, Value::Int(val)) => {
+                let span = match ty {
+                    TyKind::Primitive(prim) => values.primitive(prim),
+                    _ => values.primitive(&PrimitiveType::Int),
+                };
+
+                // Integers given to a float type are written as floats
+                let text = match ty {
+                    TyKind::Primitive(
+                        PrimitiveType::Float | PrimitiveType::F32 | PrimitiveType::Duration,
+                    ) => format!("{:?}", *val as f64),
+                    _ => val.to_string(),
+                };
+                return render_span(
+                    span,
+                    dest,
+                    Scope::new("value").add_text("value", &text),
+                    indent,
+                    template,
+                );
+            }
+            (ty, Value::Float(val)) => {
+                let span = match ty {
+                    TyKind::Primitive(prim) => values.primitive(prim),
+                    _ => values.primitive(&PrimitiveType::Float),
+                };
+
+                let text = format!("{:?}", val);
+                return render_span(
+                    span,
+                    dest,
+                    Scope::new("value").add_text("value", &text),
+                    indent,
+                    template,
+                );
+            }
+            (ty, Value::String(val)) => {
+                let span = match ty {
+                    TyKind::Primitive(prim) => values.primitive(prim),
+                    _ => &values.string,
+                };
+                (span, Scope::new("value").add_text("value", val))
+            }
+            (ty, Value::Array(items)) => (
+                &values.array,
+                Scope::new("value_array")
+                    .add_expander(
+                        "items",
+                        ValueListExpander {
+                            ty: inner,
+                            values: items,
+                            table,
+                        },
+                    )
+                    .add_expander("ty", TypeExpander::new(ty)),
+            ),
+            (ty, Value::Tuple(items)) => (
+                &values.tuple,
+                Scope::new("value_array")
+                    .add_expander(
+                        "items",
+                        TupleItemsExpander {
+                            tys: match ty {
+                                TyKind::Tuple(tys) => tys,
+                                _ => &[],
+                            },
+                            rest: ty,
+                            values: items,
+                            table,
+                        },
+                    )
+                    .add_expander("ty", TypeExpander::new(ty)),
+            ),
+            (ty, Value::Map(entries)) => (
+                &values.map,
+                Scope::new("value_map")
+                    .add_expander(
+                        "entries",
+                        MapEntriesExpander {
+                            ty: inner,
+                            entries,
+                            table,
+                        },
+                    )
+                    .add_expander("ty", TypeExpander::new(ty)),
+            ),
+            (_, Value::Variant { enum_name, variant }) => (
+                &values.variant,
+                Scope::new("value_variant")
+                    .add_text("enum", enum_name)
+                    .add_text("name", variant),
+            ),
+        };
+
+        render_span(span, dest, scope, indent, template)
+    }
+}
+
+/* --------- */
+
+/// Writes the default value of a field, or nothing if it has none
+pub struct DefaultExpander<'a>(/* default: */ pub Option<&'a DefaultValue>);
+
+impl<'a, W: Write> Expander<W> for DefaultExpander<'a> {
+    fn expand(
+        &mut self,
+        dest: &mut W,
+        indent: u16,
+        opts: &ExpandOptions,
+        template: &Template<'_>,
+    ) -> io::Result<()> {
+        match self.0 {
+            Some(default) => {
+                ValueExpander::new(&default.ty, &default.value).expand(dest, indent, opts, template)
+            }
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+/// A filter, given the text to transform and the reserved words of the template
+pub type Filter = fn(&str, &HashSet<&str>) -> String;
+
+/// Returns the filter named `name`, as used in a `%var|name%` replacer
+pub fn find_filter(name: &str) -> Option<Filter> {
+    match name {
+        "pascal" => Some(|text, _| pascal_case(text)),
+        "camel" => Some(|text, _| camel_case(text)),
+        "snake" => Some(|text, _| snake_case(text)),
+        "screaming" => Some(|text, _| screaming_case(text)),
+        "quote" => Some(|text, _| quote(text)),
+        "key" => Some(|text, _| key(text)),
+        "jsdoc" => Some(|text, _| jsdoc(text)),
+        "docstring" => Some(|text, _| docstring(text)),
+        "ident" => Some(ident),
+        _ => None,
+    }
+}
+
+/// Applies the filters in `names` to `text`, from left to right. Several filters are
+/// chained with a `|`, e.g `%name|snake|quote%`. Templates are validated when they are
+/// compiled, so every filter is known to exist
+pub fn apply_filter(names: &str, text: &str, keywords: &HashSet<&str>) -> String {
+    names.split('|').fold(text.to_string(), |text, name| {
+        let filter = find_filter(name).unwrap_or_else(|| panic!("Unknown Filter \"{}\"", name));
+        filter(&text, keywords)
+    })
+}
+
+/// Splits an identifier into its words, at separators (`_`, `-` and whitespace) and at
+/// changes of case. A run of capitals is a single word, e.g `HTTPServer` => `HTTP`, `Server`,
+/// along with a plural `s` closing it, e.g `userIDs` => `user`, `IDs`
+fn words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+
+    for part in text.split(|c: char| c == '_' || c == '-' || c.is_whitespace()) {
+        let chars = part.char_indices().collect::<Vec<_>>();
+        let mut start = 0;
+
+        for i in 1..chars.len() {
+            let (index, cur) = chars[i];
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|(_, c)| *c);
+            let after = chars.get(i + 2).map(|(_, c)| *c);
+
+            // The `s` of `IDs` or `IDsList`, rather than the start of a word `Ds`
+            let plural = next == Some('s') && !after.is_some_and(char::is_lowercase);
+
+            // `aB` or the `S` of `PServer`
+            let boundary = cur.is_uppercase()
+                && (!prev.is_uppercase() || (next.is_some_and(char::is_lowercase) && !plural));
+
+            if boundary {
+                words.push(&part[start..index]);
+                start = index;
+            }
+        }
+
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+
+    words
+}
+
+/// Uppercases the first letter of `word`, keeping the rest as is
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .into_iter()
+        .flat_map(char::to_uppercase)
+        .chain(chars)
+        .collect()
+}
+
+/// `profilePictureId` / `profile_picture_id` => `ProfilePictureId`
+fn pascal_case(text: &str) -> String {
+    words(text).into_iter().map(capitalize).collect()
+}
+
+/// `ProfilePictureId` / `profile_picture_id` => `profilePictureId`
+fn camel_case(text: &str) -> String {
+    words(text)
+        .into_iter()
+        .enumerate()
+        .map(|(i, word)| match i {
+            0 => word.to_lowercase(),
+            _ => capitalize(word),
+        })
+        .collect()
+}
+
+/// `profilePictureId` => `profile_picture_id`
+pub(crate) fn snake_case(text: &str) -> String {
+    words(text)
+        .into_iter()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `profilePictureId` => `PROFILE_PICTURE_ID`
+fn screaming_case(text: &str) -> String {
+    words(text)
+        .into_iter()
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `say "hi"` => `"say \"hi\""`, a string literal valid in most languages
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// `ends with */` => `ends with *\/`, text that can not close the `/** */` comment it is in
+fn jsdoc(text: &str) -> String {
+    text.replace("*/", "*\\/")
+}
+
+/// `say "hi" \o/` => `say \"hi\" \\o/`, text that can not close the `"""` docstring it is
+/// in, nor be read as an escape sequence
+fn docstring(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Is `text` made of letters, digits and underscores, not starting with a digit ?
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// `access_token` => `access_token`, `access-token` => `"access-token"`. An object key
+/// valid in JavaScript, quoted only when needed
+fn key(text: &str) -> String {
+    match is_identifier(text) {
+        true => text.to_string(),
+        false => quote(text),
+    }
+}
+
+/// `class` => `class_`, if `class` is listed in the `#keywords` section of the template.
+/// Other words are kept as is
+fn ident(text: &str, keywords: &HashSet<&str>) -> String {
+    match keywords.contains(text) {
+        true => format!("{}_", text),
+        false => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_split_at_case_changes() {
+        assert_eq!(words("profilePictureId"), ["profile", "Picture", "Id"]);
+        assert_eq!(words("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(words("access-token_value"), ["access", "token", "value"]);
+        assert_eq!(words("version2"), ["version2"]);
+        assert_eq!(words("A"), ["A"]);
+        assert!(words("").is_empty());
+    }
+
+    #[test]
+    fn words_keep_plural_capitals_together() {
+        assert_eq!(words("userIDs"), ["user", "IDs"]);
+        assert_eq!(words("IDsList"), ["IDs", "List"]);
+        assert_eq!(words("URLsById"), ["URLs", "By", "Id"]);
+        assert_eq!(words("HTTPService"), ["HTTP", "Service"]);
+    }
+
+    #[test]
+    fn case_filters() {
+        assert_eq!(snake_case("userIDs"), "user_ids");
+        assert_eq!(screaming_case("userIDs"), "USER_IDS");
+        assert_eq!(pascal_case("user_ids"), "UserIds");
+        assert_eq!(camel_case("HTTPServer"), "httpServer");
+        assert_eq!(snake_case("fooBar"), snake_case("foo_bar"));
+    }
+
+    #[test]
+    fn quote_and_key() {
+        assert_eq!(quote("say \"hi\"\n"), r#""say \"hi\"\n""#);
+        assert_eq!(key("access_token"), "access_token");
+        assert_eq!(key("access-token"), r#""access-token""#);
+    }
+
+    #[test]
+    fn doc_comments_can_not_be_closed() {
+        assert_eq!(jsdoc("a */ b"), r"a *\/ b");
+        assert_eq!(docstring(r#"say """hi""""#), r#"say \"\"\"hi\"\"\""#);
+        assert_eq!(docstring(r"trailing \"), r"trailing \\");
+    }
+
+    #[test]
+    fn ident_escapes_keywords() {
+        let keywords = HashSet::from(["class"]);
+        assert_eq!(ident("class", &keywords), "class_");
+        assert_eq!(ident("klass", &keywords), "klass");
+    }
+}
@@ -1,61 +1,279 @@
-use crate::items::Program;
-use std::io::{self, Write};
+use crate::items::{Item, PrimitiveType, Program, TyKind, UNION_TAG};
+use std::io::Write;
 
 mod emit;
+mod error;
 mod expander;
+pub(crate) mod filter;
 mod scope;
 mod template;
 
 use emit::{render_span, SpanWriter};
 use expander::msg_enum::EnumVariantsExpander;
-use expander::msg_struct::StructFieldsExpander;
+use expander::msg_service::ServiceMethodsExpander;
+use expander::msg_struct::{BaseExpander, Owner, StructFieldsExpander};
+use expander::msg_union::{UnionDefaultsExpander, UnionVariantsExpander};
+use expander::text::TextListExpander;
 use expander::typ::TypeExpander;
+use expander::value::ValueExpander;
 use scope::Scope;
-use template::compile_template;
 
-pub fn render_template<'a, W: Write>(
-    source: &'a str,
+pub use error::{RenderError, TemplateError, TemplateErrorKind};
+pub use template::compile_template;
+
+/// Renders `program` with the template in `source`.
+///
+/// The template is validated before anything is written to `dest`. A `flavor` selects the
+/// alternate `section@flavor` of every top level section (the prelude, imports and items)
+/// that the template defines, e.g to opt into extra output. A `#types@flavor` or
+/// `#values@flavor` table likewise replaces the default `#types` or `#values`
+pub fn render_template<W: Write>(
+    source: &str,
     program: &Program,
+    flavor: Option<&str>,
     mut dest: W,
-) -> io::Result<()> {
-    let template = compile_template(source);
+) -> Result<(), RenderError> {
+    let mut template = compile_template(source)?;
 
-    let mut writer = SpanWriter::new(&mut dest);
+    if let Some(table) = flavor.and_then(|flavor| template.alternate_types.get(flavor)) {
+        template.types = table.clone();
+    }
+
+    if let Some(table) = flavor.and_then(|flavor| template.alternate_values.get(flavor)) {
+        template.values = table.clone();
+    }
 
-    writer.write_str(template.prelude);
+    let section = |name, default| match flavor {
+        Some(flavor) => template.alternate(name, default, flavor),
+        None => default,
+    };
 
-    for enum_ in program.enums.iter() {
-        let scope = Scope::new()
-            .add_text("name", &enum_.name)
-            .add_expander("variants", EnumVariantsExpander::new(enum_.variants.iter()));
+    let mut writer = SpanWriter::new(&mut dest);
 
-        writer.write_char('\n')?;
-        writer.write_char('\n')?;
+    render_span::<W>(
+        section("prelude", &template.prelude),
+        &mut writer,
+        program_flags(program),
+        0,
+        &template,
+    )?;
 
-        render_span::<W>(&template.message_enum, &mut writer, scope, 0, &template)?;
+    if !program.imports.is_empty() {
+        writer.write_char('\n')?;
     }
 
-    for struct_ in program.structs.iter() {
-        let scope = Scope::new()
-            .add_text("name", &struct_.name)
-            .add_expander("fields", StructFieldsExpander::new(struct_.fields.iter()));
+    for import in program.imports.iter() {
+        let scope = Scope::new("import")
+            .add_text("path", &import.path)
+            .add_text("module", &import.module)
+            .add_expander("names", TextListExpander(&import.names));
 
         writer.write_char('\n')?;
-        writer.write_char('\n')?;
 
-        render_span::<W>(&template.message_struct, &mut writer, scope, 0, &template)?;
+        render_span::<W>(
+            section("import", &template.import),
+            &mut writer,
+            scope,
+            0,
+            &template,
+        )?;
     }
 
-    for alias in program.type_aliases.iter() {
-        let scope = Scope::new()
-            .add_text("name", &alias.name)
-            .add_expander("T", TypeExpander(&alias.typ));
+    for item in program.items.iter() {
+        let (span, scope) = match item {
+            Item::Struct(struct_) => {
+                let owner = Owner {
+                    name: struct_.name.clone(),
+                    params: &struct_.params,
+                };
+
+                (
+                    section("message_struct", &template.message_struct),
+                    Scope::new("message_struct")
+                        .add_text("name", &struct_.name)
+                        .add_doc(&struct_.doc)
+                        .add_attributes(&struct_.attributes)
+                        .add_flag("has_fields", struct_.all_fields().next().is_some())
+                        .add_flag(
+                            "has_defaults",
+                            struct_.all_fields().any(|field| field.default.is_some()),
+                        )
+                        .add_flag(
+                            "has_optional_fields",
+                            struct_.all_fields().any(|field| field.optional),
+                        )
+                        .add_flag("is_generic", !struct_.params.is_empty())
+                        .add_flag("has_base", struct_.base.is_some())
+                        .add_flag("has_own_fields", !struct_.fields.is_empty())
+                        .add_expander("params", TextListExpander(&struct_.params))
+                        .add_expander("base", BaseExpander(struct_.base.as_ref()))
+                        // Targets without inheritance write every field, the others only
+                        // write the fields declared in the struct itself
+                        .add_expander(
+                            "fields",
+                            StructFieldsExpander::new(owner.clone(), struct_.all_fields()),
+                        )
+                        .add_expander(
+                            "own_fields",
+                            StructFieldsExpander::new(owner.clone(), struct_.fields.iter()),
+                        )
+                        .add_expander(
+                            "defaults",
+                            StructFieldsExpander::new(owner, struct_.all_fields())
+                                .with_defaults_only(),
+                        ),
+                )
+            }
+            Item::Enum(enum_) => (
+                section(
+                    "message_enum",
+                    template.alternate(
+                        "message_enum",
+                        &template.message_enum,
+                        if enum_.is_int_enum() { "int" } else { "string" },
+                    ),
+                ),
+                Scope::new("message_enum")
+                    .add_text("name", &enum_.name)
+                    .add_doc(&enum_.doc)
+                    .add_attributes(&enum_.attributes)
+                    .add_flag("is_int_enum", enum_.is_int_enum())
+                    .add_expander(
+                        "variants",
+                        EnumVariantsExpander::new(&enum_.name, enum_.variants.iter()),
+                    ),
+            ),
+            Item::TypeAlias(alias) => (
+                section("type_alias", &template.type_alias),
+                Scope::new("type_alias")
+                    .add_text("name", &alias.name)
+                    .add_doc(&alias.doc)
+                    .add_attributes(&alias.attributes)
+                    .add_flag("is_nullable", matches!(alias.typ, TyKind::Nullable(..)))
+                    .add_flag("is_generic", !alias.params.is_empty())
+                    .add_expander("params", TextListExpander(&alias.params))
+                    .add_expander("T", TypeExpander::new(&alias.typ)),
+            ),
+            Item::Union(union_) => (
+                section("message_union", &template.message_union),
+                Scope::new("message_union")
+                    .add_text("name", &union_.name)
+                    .add_doc(&union_.doc)
+                    .add_attributes(&union_.attributes)
+                    .add_text("tag", UNION_TAG)
+                    .add_flag(
+                        "has_defaults",
+                        union_
+                            .variants
+                            .iter()
+                            .flat_map(|variant| variant.fields.iter())
+                            .any(|field| field.default.is_some()),
+                    )
+                    .add_expander(
+                        "variants",
+                        UnionVariantsExpander::new(&union_.name, union_.variants.iter()),
+                    )
+                    .add_expander(
+                        "defaults",
+                        UnionDefaultsExpander::new(&union_.name, &union_.variants),
+                    ),
+            ),
+            Item::Service(service) => (
+                section("service", &template.service),
+                Scope::new("service")
+                    .add_text("name", &service.name)
+                    .add_doc(&service.doc)
+                    .add_attributes(&service.attributes)
+                    .add_expander(
+                        "methods",
+                        ServiceMethodsExpander::new(service.methods.iter()),
+                    ),
+            ),
+            Item::Constant(constant) => (
+                section("constant", &template.constant),
+                Scope::new("constant")
+                    .add_text("name", &constant.name)
+                    .add_doc(&constant.doc)
+                    .add_attributes(&constant.attributes)
+                    .add_expander("ty", TypeExpander::new(&constant.typ))
+                    .add_expander("resolved_ty", TypeExpander::new(&constant.resolved))
+                    .add_expander(
+                        "value",
+                        ValueExpander::new(&constant.resolved, &constant.value),
+                    ),
+            ),
+        };
+
+        // Targets without a section for the item leave it out
+        if span.instructions.is_empty() {
+            continue;
+        }
 
         writer.write_char('\n')?;
         writer.write_char('\n')?;
 
-        render_span::<W>(&template.type_alias, &mut writer, scope, 0, &template)?;
+        render_span::<W>(span, &mut writer, scope, 0, &template)?;
     }
 
     Ok(())
 }
+
+/// Flags describing the whole program, which let the prelude only include what is used
+fn program_flags<'a, W: Write>(program: &Program) -> Scope<'a, W> {
+    let has_item = |pred: fn(&Item) -> bool| program.items.iter().any(pred);
+    let has_type = |pred: fn(&TyKind) -> bool| {
+        program
+            .items
+            .iter()
+            .flat_map(Item::types)
+            .any(|ty| ty.any(&pred))
+    };
+
+    Scope::new("prelude")
+        .add_flag("has_imports", !program.imports.is_empty())
+        .add_flag(
+            "has_structs",
+            has_item(|item| matches!(item, Item::Struct(..))),
+        )
+        .add_flag("has_enums", has_item(|item| matches!(item, Item::Enum(..))))
+        .add_flag(
+            "has_int_enums",
+            has_item(|item| matches!(item, Item::Enum(enum_) if enum_.is_int_enum())),
+        )
+        .add_flag(
+            "has_string_enums",
+            has_item(|item| matches!(item, Item::Enum(enum_) if !enum_.is_int_enum())),
+        )
+        .add_flag(
+            "has_unions",
+            has_item(|item| matches!(item, Item::Union(..))),
+        )
+        .add_flag(
+            "has_services",
+            has_item(|item| matches!(item, Item::Service(..))),
+        )
+        .add_flag(
+            "has_nullable",
+            has_type(|ty| matches!(ty, TyKind::Nullable(..))),
+        )
+        .add_flag("has_arrays", has_type(|ty| matches!(ty, TyKind::Array(..))))
+        .add_flag("has_maps", has_type(|ty| matches!(ty, TyKind::Map(..))))
+        .add_flag("has_tuples", has_type(|ty| matches!(ty, TyKind::Tuple(..))))
+        .add_flag(
+            "has_int64",
+            has_type(|ty| {
+                matches!(
+                    ty,
+                    TyKind::Primitive(PrimitiveType::I64 | PrimitiveType::U64)
+                )
+            }),
+        )
+        .add_flag(
+            "has_bytes",
+            has_type(|ty| matches!(ty, TyKind::Primitive(PrimitiveType::Bytes))),
+        )
+        .add_flag(
+            "has_durations",
+            has_type(|ty| matches!(ty, TyKind::Primitive(PrimitiveType::Duration))),
+        )
+}
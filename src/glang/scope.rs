@@ -1,4 +1,7 @@
-use super::expander::{text::TextExpander, Expander};
+use super::expander::text::{AttributeExpander, TextExpander, TextListExpander};
+use super::expander::Expander;
+use super::template::SectionSpec;
+use crate::items::{attribute_spec, find_attribute, Attribute, ATTRIBUTES};
 
 use std::collections::HashMap;
 use std::io::Write;
@@ -8,13 +11,44 @@ struct ScopeEntry<'a, W> {
 }
 
 pub struct Scope<'a, W> {
+    /// The section (or table entry) rendered with this scope. Its spec lists exactly the
+    /// variables and flags the scope must hold
+    section: &'static str,
     entries: HashMap<&'static str, ScopeEntry<'a, W>>,
+    /// Flags tested by `%if%` blocks
+    flags: HashMap<&'static str, bool>,
 }
 
 impl<'a, W> Scope<'a, W> {
-    pub fn new() -> Self {
+    pub fn new(section: &'static str) -> Self {
         Self {
+            section,
             entries: HashMap::new(),
+            flags: HashMap::new(),
+        }
+    }
+
+    /// Checks that the scope holds every variable and flag its section is validated
+    /// against. Attributes are only given to the sections written on their target
+    pub fn check_complete(&self) {
+        let spec = SectionSpec::of(self.section);
+
+        for (name, _) in spec.variables {
+            debug_assert!(
+                self.entries.contains_key(name),
+                "Variable %{}% of section \"{}\" is not given",
+                name,
+                self.section
+            );
+        }
+
+        for name in spec.flags {
+            debug_assert!(
+                self.flags.contains_key(name),
+                "Flag %if {}% of section \"{}\" is not given",
+                name,
+                self.section
+            );
         }
     }
 
@@ -25,7 +59,38 @@ impl<'a, W> Scope<'a, W> {
         self.add_expander(name, TextExpander(text))
     }
 
+    /// Adds the lines of a doc comment as `%doc%`, along with the `has_doc` flag
+    pub fn add_doc(self, doc: &'a [String]) -> Self
+    where
+        W: Write,
+    {
+        self.add_flag("has_doc", !doc.is_empty())
+            .add_expander("doc", TextListExpander(doc))
+    }
+
+    /// Adds every known attribute as a flag telling whether it is present, and as a
+    /// variable holding its first argument
+    pub fn add_attributes(mut self, attributes: &'a [Attribute]) -> Self
+    where
+        W: Write,
+    {
+        for spec in ATTRIBUTES {
+            let attribute = find_attribute(attributes, spec.name);
+            self = self
+                .add_flag(spec.name, attribute.is_some())
+                .add_expander(spec.name, AttributeExpander(attribute));
+        }
+        self
+    }
+
     pub fn add_expander<E: Expander<W> + 'a>(mut self, name: &'static str, expander: E) -> Self {
+        debug_assert!(
+            SectionSpec::of(self.section).has_variable(name) || attribute_spec(name).is_some(),
+            "Variable %{}% is not part of section \"{}\"",
+            name,
+            self.section
+        );
+
         self.entries.insert(
             name,
             ScopeEntry {
@@ -43,4 +108,22 @@ impl<'a, W> Scope<'a, W> {
                 panic!("Unknown variable %{}%", name);
             })
     }
+
+    pub fn add_flag(mut self, name: &'static str, value: bool) -> Self {
+        debug_assert!(
+            SectionSpec::of(self.section).has_flag(name) || attribute_spec(name).is_some(),
+            "Flag %if {}% is not part of section \"{}\"",
+            name,
+            self.section
+        );
+
+        self.flags.insert(name, value);
+        self
+    }
+
+    pub fn get_flag(&self, name: &str) -> bool {
+        *self.flags.get(name).unwrap_or_else(|| {
+            panic!("Unknown flag %if {}%", name);
+        })
+    }
 }
@@ -1,11 +1,30 @@
+use std::collections::{HashMap, HashSet};
+
+use super::error::{TemplateError, TemplateErrorKind};
+use super::filter::find_filter;
+use crate::items::{attribute_spec, AttributeTarget, PrimitiveType};
+
 #[derive(Debug, Clone, Default)]
-pub struct ExpandOptions {
+pub struct ExpandOptions<'t> {
     /// The delimeter between items emitted from this (multi) variable
     pub delimeter: Option<String>,
     /// Should the delimeter be emitted after the last item ?
     pub trailing: bool,
     /// Should all the items be emitted on a single line ?
     pub inline: bool,
+    /// Name of the alternate visitor used to render each item (`%var@name%`)
+    pub visitor: Option<String>,
+    /// Filters applied to the text of this variable (`%var|name%` or `%var|a|b%`)
+    pub filter: Option<String>,
+    /// Span used to render each item instead of a visitor, i.e the body of a `%for%` loop
+    pub body: Option<&'t TemplateSpan<'t>>,
+}
+
+/// Condition of an `%if%` block: a flag of the current scope, negated with a `!`
+#[derive(Clone, Debug)]
+pub struct Condition<'t> {
+    pub flag: &'t str,
+    pub negated: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -16,7 +35,29 @@ pub enum Instruction<'t> {
 
     Literal(&'t str),
 
-    Expand { var: &'t str, opts: ExpandOptions },
+    Expand {
+        var: &'t str,
+        opts: ExpandOptions<'t>,
+    },
+
+    /// `%if flag%...%else%...%end%`
+    If {
+        cond: Condition<'t>,
+        then: TemplateSpan<'t>,
+        otherwise: TemplateSpan<'t>,
+    },
+
+    /// `%for item in var%...%end%`, renders `body` once for every item of `var`. Inside
+    /// the body, the variables of an item are accessed as `%item.name%`
+    For {
+        item: &'t str,
+        var: &'t str,
+        opts: ExpandOptions<'t>,
+        body: TemplateSpan<'t>,
+        /// Is the loop on a line of its own ? If so its body is indented as written,
+        /// rather than relative to the current line
+        block: bool,
+    },
 }
 
 #[derive(Clone, Debug, Default)]
@@ -24,18 +65,27 @@ pub struct TemplateSpan<'t> {
     pub instructions: Vec<Instruction<'t>>,
 }
 
-fn parse_replacer<'t>(source: &'t str) -> (&'t str, ExpandOptions) {
-    // %var/,/+/i%
+fn parse_replacer(source: &str) -> (&str, ExpandOptions<'_>) {
+    // %var@visitor/,/+/i% or %var|filter%
     let parts = source.split('/').collect::<Vec<_>>();
 
-    let var = parts[0];
+    let (var, filter) = match parts[0].split_once('|') {
+        Some((var, filter)) => (var, Some(filter.to_string())),
+        None => (parts[0], None),
+    };
+
+    let (var, visitor) = match var.split_once('@') {
+        Some((var, visitor)) => (var, Some(visitor.to_string())),
+        None => (var, None),
+    };
 
     let mut delimeter = None;
     let mut trailing = false;
     let mut inline = false;
 
     if parts.len() > 1 {
-        delimeter = Some(parts[1].to_string());
+        // Allow delimeters to span multiple lines, e.g to separate items by a blank line
+        delimeter = Some(parts[1].replace("\\n", "\n"));
         trailing = parts.len() > 2 && parts[2] == "+";
         inline = parts.len() > 3 && parts[3] == "i";
     }
@@ -44,51 +94,259 @@ fn parse_replacer<'t>(source: &'t str) -> (&'t str, ExpandOptions) {
         delimeter,
         trailing,
         inline,
+        visitor,
+        filter,
+        body: None,
     };
 
     (var, opts)
 }
 
-pub fn compile_span<'t>(content: &'t str) -> TemplateSpan<'t> {
+/// Anything written between a pair of `%`
+enum Tag<'t> {
+    Replacer(&'t str, ExpandOptions<'t>),
+    If(Condition<'t>),
+    Else,
+    End,
+    For {
+        item: &'t str,
+        var: &'t str,
+        opts: ExpandOptions<'t>,
+    },
+}
+
+impl<'t> Tag<'t> {
+    fn parse(source: &'t str) -> Result<Self, TemplateErrorKind> {
+        if let Some(flag) = source.strip_prefix("if ") {
+            let flag = flag.trim();
+            let (flag, negated) = match flag.strip_prefix('!') {
+                Some(flag) => (flag.trim_start(), true),
+                None => (flag, false),
+            };
+
+            return Ok(Tag::If(Condition { flag, negated }));
+        }
+
+        // The variable of a loop takes the same options as a replacer, e.g a delimeter
+        if let Some(rest) = source.strip_prefix("for ") {
+            return match rest.split_once(" in ") {
+                Some((item, var)) if !item.trim().is_empty() && !var.trim().is_empty() => {
+                    let (var, opts) = parse_replacer(var.trim_start());
+                    Ok(Tag::For {
+                        item: item.trim(),
+                        var,
+                        opts,
+                    })
+                }
+                _ => Err(TemplateErrorKind::MalformedLoop(source.to_string())),
+            };
+        }
+
+        Ok(match source.trim() {
+            "else" => Tag::Else,
+            "end" => Tag::End,
+            _ => {
+                let (var, opts) = parse_replacer(source);
+                Tag::Replacer(var, opts)
+            }
+        })
+    }
+
+    fn is_control(&self) -> bool {
+        !matches!(self, Tag::Replacer(..))
+    }
+}
+
+/// An `%if%` or `%for%` block whose `%end%` has not been reached yet
+struct OpenBlock<'t> {
+    /// The tag that opened the block
+    source: &'t str,
+    kind: BlockKind<'t>,
+    /// Instructions of the enclosing span, up to the start of this block
+    outer: Vec<Instruction<'t>>,
+    /// Does the block start the span ? Its lines then end with a newline rather than
+    /// start with one, so that nothing is left behind when the block renders nothing
+    leading: bool,
+}
+
+impl<'t> OpenBlock<'t> {
+    /// Ends the lines of a leading block, before its `%else%` or `%end%`
+    fn finish_lines(&self, instructions: &mut Vec<Instruction<'t>>, is_tail: &mut bool) {
+        if self.leading {
+            if *is_tail {
+                instructions.push(Instruction::Newline);
+            }
+
+            *is_tail = false;
+        }
+    }
+}
+
+enum BlockKind<'t> {
+    If {
+        cond: Condition<'t>,
+        /// Set once the `%else%` is reached
+        then: Option<Vec<Instruction<'t>>>,
+    },
+    For {
+        item: &'t str,
+        var: &'t str,
+        opts: ExpandOptions<'t>,
+        block: bool,
+    },
+}
+
+/// Compiles `content`, which is a part of `template`
+pub fn compile_span<'t>(
+    template: &'t str,
+    content: &'t str,
+) -> Result<TemplateSpan<'t>, TemplateError> {
     let mut instructions: Vec<Instruction> = vec![];
+    let mut blocks: Vec<OpenBlock> = vec![];
 
     let re = regex::Regex::new(r"%([^%]+)%").unwrap();
 
     let mut is_tail = false;
 
     for line in content.lines() {
-        if is_tail {
-            instructions.push(Instruction::Newline);
-        } else {
-            is_tail = true;
-        }
+        let tags = re
+            .captures_iter(line)
+            .map(|caps| {
+                let whole = caps.get(0).unwrap();
+                let source = caps.get(1).unwrap().as_str();
+                let tag =
+                    Tag::parse(source).map_err(|kind| TemplateError::at(template, source, kind))?;
+                Ok((whole.start(), whole.end(), source, tag))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Lines having nothing but blocks' tags are not part of the output
+        let control_only = !tags.is_empty()
+            && tags.iter().all(|(.., tag)| tag.is_control())
+            && re.replace_all(line, "").trim().is_empty();
 
         let mut last_end = 0;
 
-        let leading_whitespace_count =
-            line.chars().take_while(|c| c.is_whitespace()).count() as u16;
+        if !control_only {
+            if is_tail {
+                instructions.push(Instruction::Newline);
+            } else {
+                is_tail = true;
+            }
 
-        if leading_whitespace_count > 0 {
-            instructions.push(Instruction::Indent(leading_whitespace_count));
+            let leading_whitespace_count =
+                line.chars().take_while(|c| c.is_whitespace()).count() as u16;
 
-            // start the first section after the indent
-            last_end = leading_whitespace_count as usize;
-        }
+            if leading_whitespace_count > 0 {
+                instructions.push(Instruction::Indent(leading_whitespace_count));
 
-        for caps in re.captures_iter(line) {
-            let w_start = caps.get(0).unwrap().start();
-            let w_end = caps.get(0).unwrap().end();
+                // start the first section after the indent
+                last_end = leading_whitespace_count as usize;
+            }
+        } else {
+            last_end = line.len();
+        }
 
+        for (w_start, w_end, source, tag) in tags {
             if w_start > last_end {
                 instructions.push(Instruction::Literal(&line[last_end..w_start]));
             }
 
-            let rep_source = caps.get(1).unwrap().as_str();
-            let (var, opts) = parse_replacer(rep_source);
+            last_end = last_end.max(w_end);
 
-            instructions.push(Instruction::Expand { var, opts });
+            let unexpected = || {
+                TemplateError::at(
+                    template,
+                    source,
+                    TemplateErrorKind::UnexpectedTag(source.trim().to_string()),
+                )
+            };
+
+            match tag {
+                Tag::Replacer(var, opts) => instructions.push(Instruction::Expand { var, opts }),
+
+                Tag::If(cond) => blocks.push(OpenBlock {
+                    source,
+                    kind: BlockKind::If { cond, then: None },
+                    outer: std::mem::take(&mut instructions),
+                    leading: !is_tail,
+                }),
+
+                Tag::For {
+                    item,
+                    var,
+                    mut opts,
+                } => {
+                    // Every line of the body starts (or ends) with a newline already
+                    opts.inline = true;
+
+                    blocks.push(OpenBlock {
+                        source,
+                        kind: BlockKind::For {
+                            item,
+                            var,
+                            opts,
+                            block: control_only,
+                        },
+                        outer: std::mem::take(&mut instructions),
+                        leading: !is_tail,
+                    });
+                }
 
-            last_end = w_end;
+                Tag::Else => match blocks.last_mut() {
+                    Some(
+                        block @ OpenBlock {
+                            kind: BlockKind::If { then: None, .. },
+                            ..
+                        },
+                    ) => {
+                        block.finish_lines(&mut instructions, &mut is_tail);
+
+                        if let BlockKind::If { then, .. } = &mut block.kind {
+                            *then = Some(std::mem::take(&mut instructions));
+                        }
+                    }
+                    _ => return Err(unexpected()),
+                },
+
+                Tag::End => {
+                    let block = blocks.pop().ok_or_else(unexpected)?;
+                    block.finish_lines(&mut instructions, &mut is_tail);
+
+                    let inner = std::mem::replace(&mut instructions, block.outer);
+
+                    instructions.push(match block.kind {
+                        BlockKind::If { cond, then } => {
+                            let (then, otherwise) = match then {
+                                Some(then) => (then, inner),
+                                None => (inner, vec![]),
+                            };
+
+                            Instruction::If {
+                                cond,
+                                then: TemplateSpan { instructions: then },
+                                otherwise: TemplateSpan {
+                                    instructions: otherwise,
+                                },
+                            }
+                        }
+                        BlockKind::For {
+                            item,
+                            var,
+                            opts,
+                            block,
+                        } => Instruction::For {
+                            item,
+                            var,
+                            opts,
+                            body: TemplateSpan {
+                                instructions: inner,
+                            },
+                            block,
+                        },
+                    });
+                }
+            }
         }
 
         if last_end < line.len() {
@@ -96,7 +354,43 @@ pub fn compile_span<'t>(content: &'t str) -> TemplateSpan<'t> {
         }
     }
 
-    TemplateSpan { instructions }
+    if let Some(block) = blocks.pop() {
+        return Err(TemplateError::at(
+            template,
+            block.source,
+            TemplateErrorKind::UnclosedBlock(block.source.trim().to_string()),
+        ));
+    }
+
+    // Nothing follows the block(s) ending the span, so their lines need no ending
+    strip_trailing_newline(&mut instructions);
+
+    Ok(TemplateSpan { instructions })
+}
+
+/// Removes the newline that ends the lines of a leading block at the end of
+/// `instructions`, if any. Returns whether a newline was removed
+fn strip_trailing_newline(instructions: &mut Vec<Instruction>) -> bool {
+    match instructions.last_mut() {
+        Some(Instruction::Newline) => {
+            instructions.pop();
+            true
+        }
+        Some(Instruction::If {
+            then, otherwise, ..
+        }) => {
+            let then = strip_trailing_newline(&mut then.instructions);
+            let otherwise = strip_trailing_newline(&mut otherwise.instructions);
+            then || otherwise
+        }
+        Some(Instruction::For { opts, body, .. }) => {
+            // The items now have to be put on separate lines by the loop itself
+            let stripped = strip_trailing_newline(&mut body.instructions);
+            opts.inline &= !stripped;
+            stripped
+        }
+        _ => false,
+    }
 }
 
 /* ==================================== */
@@ -106,7 +400,9 @@ pub fn compile_span<'t>(content: &'t str) -> TemplateSpan<'t> {
 #[derive(Debug, Default)]
 struct TemplateSections<'a> {
     prelude: &'a str,
+    import: &'a str,
     types: &'a str,
+    values: &'a str,
     // type_visitor: &'a str,
     // structs
     field_visitor: &'a str,
@@ -115,18 +411,79 @@ struct TemplateSections<'a> {
     enum_variant_visitor: &'a str,
     message_enum: &'a str,
     type_alias: &'a str,
+    // unions
+    union_variant_visitor: &'a str,
+    message_union: &'a str,
+    // services
+    method_visitor: &'a str,
+    service: &'a str,
+    constant: &'a str,
+    keywords: &'a str,
+    /// Alternate sections, i.e sections named as `section@name`
+    alternates: Vec<(&'a str, &'a str)>,
 }
 
-fn compile_template_sections<'a>(source: &'a str) -> TemplateSections<'a> {
-    let mut sections = TemplateSections::default();
+impl<'a> TemplateSections<'a> {
+    /// Where the body of the section `name` is stored, or None if there is no such section
+    fn slot(&mut self, name: &'a str) -> Option<&mut &'a str> {
+        Some(match name {
+            name if name.contains('@') => {
+                let (base, _) = name.split_once('@').unwrap();
+                if !ALTERNATE_BASES.contains(&base) {
+                    return None;
+                }
+                self.alternates.push((name, ""));
+                &mut self.alternates.last_mut().unwrap().1
+            }
+            "prelude" => &mut self.prelude,
+            "import" => &mut self.import,
+            "types" => &mut self.types,
+            "values" => &mut self.values,
+            "field_visitor" => &mut self.field_visitor,
+            "message_struct" => &mut self.message_struct,
+            "enum_variant_visitor" => &mut self.enum_variant_visitor,
+            "message_enum" => &mut self.message_enum,
+            "type_alias" => &mut self.type_alias,
+            "union_variant_visitor" => &mut self.union_variant_visitor,
+            "message_union" => &mut self.message_union,
+            "method_visitor" => &mut self.method_visitor,
+            "service" => &mut self.service,
+            "constant" => &mut self.constant,
+            "keywords" => &mut self.keywords,
+            _ => return None,
+        })
+    }
+}
 
-    let lines = source.split_inclusive('\n');
+/// Sections that can have alternates (`section@name`)
+const ALTERNATE_BASES: &[&str] = &[
+    "prelude",
+    "import",
+    "types",
+    "values",
+    "field_visitor",
+    "message_struct",
+    "enum_variant_visitor",
+    "message_enum",
+    "type_alias",
+    "union_variant_visitor",
+    "message_union",
+    "method_visitor",
+    "service",
+    "constant",
+    "text_visitor",
+];
+
+fn compile_template_sections<'a>(source: &'a str) -> Result<TemplateSections<'a>, TemplateError> {
+    let mut sections = TemplateSections::default();
+    let mut defined = HashSet::new();
 
-    let mut cur_section: Option<&mut &'a str> = None;
+    // The section being read: its name, its start marker and where its body is stored
+    let mut cur_section: Option<(&'a str, &'a str, &mut &'a str)> = None;
     let mut start = 0;
     let mut current = 0;
 
-    for line in lines {
+    for line in source.split_inclusive('\n') {
         let len = line.len();
         let current_new = current + len;
 
@@ -136,64 +493,320 @@ fn compile_template_sections<'a>(source: &'a str) -> TemplateSections<'a> {
             .map(|line| line.strip_suffix('\r').unwrap_or(line))
             .unwrap_or(line);
 
-        if line.trim().starts_with("#") {
-            let line = line.trim().strip_prefix("#").unwrap();
-            let (is_start, name) = if line.starts_with("end/") {
-                (false, line.strip_prefix("end/").unwrap())
-            } else {
-                (true, line)
-            };
+        if is_section_marker(line) {
+            let marker = line.trim();
+            let tag = marker.strip_prefix('#').unwrap();
+            let error = |kind| TemplateError::at(source, marker, kind);
 
-            if is_start {
-                if cur_section.is_none() {
-                    cur_section = Some(match name {
-                        "prelude" => &mut sections.prelude,
-                        "types" => &mut sections.types,
-                        // "type_visitor" => &mut sections.type_visitor,
-                        "field_visitor" => &mut sections.field_visitor,
-                        "message_struct" => &mut sections.message_struct,
-                        "enum_variant_visitor" => &mut sections.enum_variant_visitor,
-                        "message_enum" => &mut sections.message_enum,
-                        "type_alias" => &mut sections.type_alias,
-                        _ => panic!("Unknown Section \"{}\"", name),
-                    });
+            match (tag.strip_prefix("end/"), cur_section.take()) {
+                (None, None) => {
+                    let name = tag;
+                    let slot = sections.slot(name).ok_or_else(|| {
+                        error(TemplateErrorKind::UnknownSection(name.to_string()))
+                    })?;
+
+                    if !defined.insert(name) {
+                        return Err(error(TemplateErrorKind::RedefinedSection(name.to_string())));
+                    }
 
+                    cur_section = Some((name, marker, slot));
                     start = current_new;
-                } else {
-                    panic!("Already in section");
                 }
-            } else {
-                let section = cur_section.take().unwrap();
-
-                *section = &source[start..current];
+                (None, Some((parent, ..))) => {
+                    return Err(error(TemplateErrorKind::NestedSection {
+                        name: tag.to_string(),
+                        parent: parent.to_string(),
+                    }));
+                }
+                (Some(name), None) => {
+                    return Err(error(TemplateErrorKind::UnopenedSection(name.to_string())));
+                }
+                (Some(name), Some((expected, _, slot))) => {
+                    if name != expected {
+                        return Err(error(TemplateErrorKind::MismatchedEnd {
+                            expected: expected.to_string(),
+                            found: name.to_string(),
+                        }));
+                    }
+
+                    *slot = &source[start..current];
+                }
             }
         }
 
         current = current_new;
     }
 
-    sections
+    if let Some((name, marker, _)) = cur_section {
+        return Err(TemplateError::at(
+            source,
+            marker,
+            TemplateErrorKind::UnclosedSection(name.to_string()),
+        ));
+    }
+
+    Ok(sections)
 }
 
 /* ==================================== */
 /* ==================================== */
 /* ==================================== */
 
+/// How each kind of type is written, i.e the contents of a `#types` section
+#[derive(Debug, Clone, Default)]
+pub struct TypeTable<'t> {
+    pub string: TemplateSpan<'t>,
+    pub int: TemplateSpan<'t>,
+    pub float: TemplateSpan<'t>,
+    pub bool: TemplateSpan<'t>,
+    pub array: TemplateSpan<'t>,
+    pub map: TemplateSpan<'t>,
+    pub tuple: TemplateSpan<'t>,
+    pub null: TemplateSpan<'t>,
+    pub udt: TemplateSpan<'t>,
+    /// A generic type given its type arguments
+    pub generic: TemplateSpan<'t>,
+    /// A type parameter of the generic type being written
+    pub param: TemplateSpan<'t>,
+    /// Entries of the sized and semantic primitives (`i8`, `bytes`, `uuid`, ...). These
+    /// are optional, see `TypeTable::primitive`
+    pub optional: HashMap<&'static str, TemplateSpan<'t>>,
+}
+
+/// The optional entries of a `#types` section, along with the entry used in their place
+/// when they are missing. This is the entry of the type they are sent as on the wire
+const OPTIONAL_ENTRIES: &[(&str, &str)] = &[
+    ("i8", "int"),
+    ("i16", "int"),
+    ("i64", "string"),
+    ("u8", "int"),
+    ("u16", "int"),
+    ("u32", "int"),
+    ("u64", "string"),
+    ("f32", "float"),
+    ("bytes", "string"),
+    ("timestamp", "string"),
+    ("date", "string"),
+    ("duration", "float"),
+    ("uuid", "string"),
+    ("decimal", "string"),
+    ("url", "string"),
+];
+
+impl<'t> TypeTable<'t> {
+    /// Every entry of the table, keyed by its name in the `#types` section
+    fn entries(&self) -> Vec<(&'static str, &TemplateSpan<'t>)> {
+        let entries = [
+            ("string", &self.string),
+            ("int", &self.int),
+            ("float", &self.float),
+            ("bool", &self.bool),
+            ("array", &self.array),
+            ("map", &self.map),
+            ("tuple", &self.tuple),
+            ("null", &self.null),
+            ("struct", &self.udt),
+            ("generic", &self.generic),
+            ("param", &self.param),
+        ];
+
+        entries
+            .into_iter()
+            .chain(self.optional.iter().map(|(name, span)| (*name, span)))
+            .collect()
+    }
+
+    /// The entry of a primitive type. Optional entries that the table does not list are
+    /// written like the basic type they are sent as
+    pub fn primitive(&self, prim: &PrimitiveType) -> &TemplateSpan<'t> {
+        let name = match prim {
+            PrimitiveType::String => return &self.string,
+            PrimitiveType::Int => return &self.int,
+            PrimitiveType::Float => return &self.float,
+            PrimitiveType::Bool => return &self.bool,
+            _ => prim.name(),
+        };
+
+        if let Some(span) = self.optional.get(name) {
+            return span;
+        }
+
+        match fallback_entry(name) {
+            "int" => &self.int,
+            "float" => &self.float,
+            _ => &self.string,
+        }
+    }
+}
+
+/// The entry used in place of the optional entry `name` when a table does not list it
+fn fallback_entry(name: &str) -> &'static str {
+    let (_, fallback) = OPTIONAL_ENTRIES
+        .iter()
+        .find(|(entry, _)| *entry == name)
+        .unwrap();
+    fallback
+}
+
+fn compile_type_table<'t>(
+    template: &'t str,
+    source: &'t str,
+) -> Result<TypeTable<'t>, TemplateError> {
+    let mut table = TypeTable::default();
+
+    stream_parse_visitors(template, source, |name, span| {
+        match name {
+            "string" => table.string = span,
+            "int" => table.int = span,
+            "float" => table.float = span,
+            "bool" => table.bool = span,
+            "array" => table.array = span,
+            "map" => table.map = span,
+            "tuple" => table.tuple = span,
+            "null" => table.null = span,
+            "struct" => table.udt = span,
+            "generic" => table.generic = span,
+            "param" => table.param = span,
+            _ => match OPTIONAL_ENTRIES.iter().find(|(entry, _)| *entry == name) {
+                Some((entry, _)) => {
+                    table.optional.insert(entry, span);
+                }
+                None => {
+                    return Err(TemplateError::at(
+                        template,
+                        name,
+                        TemplateErrorKind::UnknownType(name.to_string()),
+                    ))
+                }
+            },
+        }
+
+        Ok(())
+    })?;
+
+    Ok(table)
+}
+
+/// How each kind of value is written, i.e the contents of a `#values` section
+#[derive(Debug, Clone, Default)]
+pub struct ValueTable<'t> {
+    pub string: TemplateSpan<'t>,
+    pub int: TemplateSpan<'t>,
+    pub float: TemplateSpan<'t>,
+    pub true_: TemplateSpan<'t>,
+    pub false_: TemplateSpan<'t>,
+    pub null: TemplateSpan<'t>,
+    /// A value that is not `null`, given to a nullable type
+    pub some: TemplateSpan<'t>,
+    pub array: TemplateSpan<'t>,
+    pub tuple: TemplateSpan<'t>,
+    pub map: TemplateSpan<'t>,
+    /// A single entry of a map
+    pub entry: TemplateSpan<'t>,
+    /// A variant of an enum
+    pub variant: TemplateSpan<'t>,
+    /// Values of the sized and semantic primitives, these fall back like the entries of
+    /// a `#types` section
+    pub optional: HashMap<&'static str, TemplateSpan<'t>>,
+}
+
+impl<'t> ValueTable<'t> {
+    /// Every entry of the table, keyed by the kind of entry it is. The kind decides the
+    /// variables the entry can use
+    fn entries(&self) -> Vec<(&'static str, &TemplateSpan<'t>)> {
+        let entries = [
+            ("value", &self.string),
+            ("value", &self.int),
+            ("value", &self.float),
+            ("value_const", &self.true_),
+            ("value_const", &self.false_),
+            ("value_const", &self.null),
+            ("value_some", &self.some),
+            ("value_array", &self.array),
+            ("value_array", &self.tuple),
+            ("value_map", &self.map),
+            ("value_entry", &self.entry),
+            ("value_variant", &self.variant),
+        ];
+
+        entries
+            .into_iter()
+            .chain(self.optional.values().map(|span| ("value", span)))
+            .collect()
+    }
+
+    /// The entry used for a number or string given to a primitive type
+    pub fn primitive(&self, prim: &PrimitiveType) -> &TemplateSpan<'t> {
+        let name = match prim {
+            PrimitiveType::Int => return &self.int,
+            PrimitiveType::Float => return &self.float,
+            PrimitiveType::String | PrimitiveType::Bool => return &self.string,
+            _ => prim.name(),
+        };
+
+        if let Some(span) = self.optional.get(name) {
+            return span;
+        }
+
+        match fallback_entry(name) {
+            "int" => &self.int,
+            "float" => &self.float,
+            _ => &self.string,
+        }
+    }
+}
+
+fn compile_value_table<'t>(
+    template: &'t str,
+    source: &'t str,
+) -> Result<ValueTable<'t>, TemplateError> {
+    let mut table = ValueTable::default();
+
+    stream_parse_visitors(template, source, |name, span| {
+        match name {
+            "string" => table.string = span,
+            "int" => table.int = span,
+            "float" => table.float = span,
+            "true" => table.true_ = span,
+            "false" => table.false_ = span,
+            "null" => table.null = span,
+            "some" => table.some = span,
+            "array" => table.array = span,
+            "tuple" => table.tuple = span,
+            "map" => table.map = span,
+            "entry" => table.entry = span,
+            "variant" => table.variant = span,
+            _ => match OPTIONAL_ENTRIES.iter().find(|(entry, _)| *entry == name) {
+                Some((entry, _)) => {
+                    table.optional.insert(entry, span);
+                }
+                None => {
+                    return Err(TemplateError::at(
+                        template,
+                        name,
+                        TemplateErrorKind::UnknownValue(name.to_string()),
+                    ))
+                }
+            },
+        }
+
+        Ok(())
+    })?;
+
+    Ok(table)
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Template<'t> {
-    pub prelude: &'t str,
+    pub prelude: TemplateSpan<'t>,
+
+    pub import: TemplateSpan<'t>,
 
     pub echo: TemplateSpan<'t>,
 
-    pub field_string: TemplateSpan<'t>,
-    pub field_int: TemplateSpan<'t>,
-    pub field_float: TemplateSpan<'t>,
-    pub field_bool: TemplateSpan<'t>,
-    pub field_array: TemplateSpan<'t>,
-    pub field_map: TemplateSpan<'t>,
-    pub field_tuple: TemplateSpan<'t>,
-    pub field_null: TemplateSpan<'t>,
-    pub field_struct: TemplateSpan<'t>,
+    pub types: TypeTable<'t>,
+
+    pub values: ValueTable<'t>,
 
     /* ... */
     // pub ast_primitive: TemplateSpan<'t>,
@@ -211,43 +824,628 @@ pub struct Template<'t> {
 
     /* Type Aliases */
     pub type_alias: TemplateSpan<'t>,
+
+    /* Unions */
+    pub union_variant: TemplateSpan<'t>,
+    pub message_union: TemplateSpan<'t>,
+
+    /* Services */
+    pub method_body: TemplateSpan<'t>,
+    pub service: TemplateSpan<'t>,
+
+    /* Constants */
+    pub constant: TemplateSpan<'t>,
+
+    /// Alternate sections, keyed by their full name (`section@name`)
+    pub alternates: HashMap<&'t str, TemplateSpan<'t>>,
+
+    /// Alternate type tables (`#types@name`), keyed by their name
+    pub alternate_types: HashMap<&'t str, TypeTable<'t>>,
+
+    /// Alternate value tables (`#values@name`), keyed by their name
+    pub alternate_values: HashMap<&'t str, ValueTable<'t>>,
+
+    /// Reserved words of the target language, listed in the `#keywords` section. These
+    /// are escaped by the `ident` filter
+    pub keywords: HashSet<&'t str>,
 }
 
-pub fn compile_template<'a>(source: &'a str) -> Template<'a> {
-    let sections = compile_template_sections(source);
-    let mut template = Template::default();
-
-    template.prelude = sections.prelude.trim();
-    template.echo = compile_span("%value%");
-
-    stream_parse_visitors(sections.types, |name, span| match name {
-        "string" => template.field_string = span,
-        "int" => template.field_int = span,
-        "float" => template.field_float = span,
-        "bool" => template.field_bool = span,
-        "array" => template.field_array = span,
-        "map" => template.field_map = span,
-        "tuple" => template.field_tuple = span,
-        "null" => template.field_null = span,
-        "struct" => template.field_struct = span,
-        _ => {}
-    });
-
-    template.field_body = compile_span(sections.field_visitor.trim());
-    template.message_struct = compile_span(sections.message_struct.trim());
-
-    template.enum_variant = compile_span(sections.enum_variant_visitor.trim());
-    template.message_enum = compile_span(sections.message_enum.trim());
-    template.type_alias = compile_span(sections.type_alias.trim());
-
-    template
+impl<'t> Template<'t> {
+    /// Returns the alternate section `section@name` if the template defines it, otherwise
+    /// falls back to `default`
+    pub fn alternate<'s>(
+        &'s self,
+        section: &str,
+        default: &'s TemplateSpan<'t>,
+        name: &str,
+    ) -> &'s TemplateSpan<'t> {
+        self.alternates
+            .get(format!("{}@{}", section, name).as_str())
+            .unwrap_or(default)
+    }
+
+    /// Returns the span used to render a single item of a multi variable. This is
+    /// `default` unless the variable asked for an alternate visitor of `section`, or is
+    /// the subject of a `%for%` loop
+    pub fn visitor<'s>(
+        &'s self,
+        section: &str,
+        default: &'s TemplateSpan<'t>,
+        opts: &ExpandOptions<'s>,
+    ) -> &'s TemplateSpan<'s> {
+        if let Some(body) = opts.body {
+            return body;
+        }
+
+        match &opts.visitor {
+            None => default,
+            Some(name) => {
+                let name = format!("{}@{}", section, name);
+                self.alternates
+                    .get(name.as_str())
+                    .unwrap_or_else(|| panic!("Unknown Section \"{}\"", name))
+            }
+        }
+    }
+
+    /// Returns the type table named `name`, or the default `#types` table if `name` is None
+    pub fn types(&self, name: Option<&str>) -> &TypeTable<'t> {
+        match name {
+            None => &self.types,
+            Some(name) => self
+                .alternate_types
+                .get(name)
+                .unwrap_or_else(|| panic!("Unknown Section \"types@{}\"", name)),
+        }
+    }
+
+    /// Returns the value table named `name`, or the default `#values` table if `name` is
+    /// None
+    pub fn values(&self, name: Option<&str>) -> &ValueTable<'t> {
+        match name {
+            None => &self.values,
+            Some(name) => self
+                .alternate_values
+                .get(name)
+                .unwrap_or_else(|| panic!("Unknown Section \"values@{}\"", name)),
+        }
+    }
+}
+
+pub fn compile_template(source: &str) -> Result<Template<'_>, TemplateError> {
+    let sections = compile_template_sections(source)?;
+    let mut template = Template {
+        prelude: compile_span(source, sections.prelude.trim())?,
+        echo: compile_span("%value%", "%value%")?,
+        types: compile_type_table(source, sections.types)?,
+        values: compile_value_table(source, sections.values)?,
+        keywords: sections.keywords.split_whitespace().collect(),
+        ..Default::default()
+    };
+
+    template.field_body = compile_span(source, sections.field_visitor.trim())?;
+    template.message_struct = compile_span(source, sections.message_struct.trim())?;
+
+    template.enum_variant = compile_span(source, sections.enum_variant_visitor.trim())?;
+    template.message_enum = compile_span(source, sections.message_enum.trim())?;
+    template.type_alias = compile_span(source, sections.type_alias.trim())?;
+
+    template.import = compile_span(source, sections.import.trim())?;
+
+    template.union_variant = compile_span(source, sections.union_variant_visitor.trim())?;
+    template.message_union = compile_span(source, sections.message_union.trim())?;
+
+    template.method_body = compile_span(source, sections.method_visitor.trim())?;
+    template.service = compile_span(source, sections.service.trim())?;
+
+    template.constant = compile_span(source, sections.constant.trim())?;
+
+    for (name, body) in sections.alternates {
+        if let Some(table) = name.strip_prefix("values@") {
+            template
+                .alternate_values
+                .insert(table, compile_value_table(source, body)?);
+            continue;
+        }
+
+        match name.strip_prefix("types@") {
+            Some(table) => {
+                template
+                    .alternate_types
+                    .insert(table, compile_type_table(source, body)?);
+            }
+            None => {
+                template
+                    .alternates
+                    .insert(name, compile_span(source, body.trim())?);
+            }
+        }
+    }
+
+    template.validate(source)?;
+
+    Ok(template)
+}
+
+/* ===================== Validation ===================== */
+
+/// A variable available to a section, along with the section whose alternates can be
+/// selected to render it (`%var@name%`)
+type Variable = (&'static str, Option<&'static str>);
+
+/// The lines of a doc comment, rendered like any other list of text
+const DOC: Option<&str> = Some("text_visitor");
+
+/// What a section (or an entry of a `#types` or `#values` section) can use. Templates are
+/// validated against these, and the scopes sections are rendered with are checked
+/// against them too, so that the two can not drift apart
+pub struct SectionSpec {
+    pub name: &'static str,
+    pub variables: &'static [Variable],
+    /// Flags that can be tested with `%if%`
+    pub flags: &'static [&'static str],
+    /// What the attributes available to the section are written on. Attributes are
+    /// available both as a flag and as a variable holding their first argument
+    pub target: Option<AttributeTarget>,
+}
+
+impl SectionSpec {
+    const fn new(name: &'static str, variables: &'static [Variable]) -> Self {
+        Self {
+            name,
+            variables,
+            flags: &[],
+            target: None,
+        }
+    }
+
+    const fn flags(self, flags: &'static [&'static str]) -> Self {
+        Self { flags, ..self }
+    }
+
+    const fn target(self, target: AttributeTarget) -> Self {
+        Self {
+            target: Some(target),
+            ..self
+        }
+    }
+
+    /// The spec of `section`. Sections and entries missing from the table can use
+    /// nothing
+    pub fn of(section: &str) -> &'static SectionSpec {
+        static EMPTY: SectionSpec = SectionSpec::new("", &[]);
+
+        SECTIONS
+            .iter()
+            .find(|spec| spec.name == section)
+            .unwrap_or(&EMPTY)
+    }
+
+    /// Returns the section whose alternates can render the variable `name` (if any), or
+    /// None if the section can not use it
+    fn variable(&self, name: &str) -> Option<Option<&'static str>> {
+        match self
+            .variables
+            .iter()
+            .find(|(candidate, _)| *candidate == name)
+        {
+            Some((_, visitor_base)) => Some(*visitor_base),
+            None if self.has_attribute(name) => Some(None),
+            None => None,
+        }
+    }
+
+    pub fn has_variable(&self, name: &str) -> bool {
+        self.variable(name).is_some()
+    }
+
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains(&name) || self.has_attribute(name)
+    }
+
+    /// Can the attribute `name` be used on the target of the section ?
+    fn has_attribute(&self, name: &str) -> bool {
+        match (attribute_spec(name), self.target) {
+            (Some(spec), Some(target)) => spec.applies_to(target),
+            _ => false,
+        }
+    }
+}
+
+const SECTIONS: &[SectionSpec] = &[
+    SectionSpec::new("prelude", &[]).flags(&[
+        "has_imports",
+        "has_structs",
+        "has_enums",
+        "has_int_enums",
+        "has_string_enums",
+        "has_unions",
+        "has_services",
+        "has_nullable",
+        "has_arrays",
+        "has_maps",
+        "has_tuples",
+        "has_int64",
+        "has_bytes",
+        "has_durations",
+    ]),
+    SectionSpec::new(
+        "import",
+        &[
+            ("path", None),
+            ("module", None),
+            ("names", Some("text_visitor")),
+        ],
+    ),
+    SectionSpec::new(
+        "field_visitor",
+        &[
+            ("name", None),
+            ("wire", None),
+            ("doc", DOC),
+            ("ty", Some("types")),
+            ("nullable_ty", Some("types")),
+            ("default", Some("values")),
+            ("owner", None),
+            ("owner_params", Some("text_visitor")),
+        ],
+    )
+    .flags(&[
+        "is_nullable",
+        "is_optional",
+        "has_doc",
+        "has_default",
+        "has_mutable_default",
+        "has_owner_params",
+    ])
+    .target(AttributeTarget::Field),
+    SectionSpec::new(
+        "message_struct",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("params", Some("text_visitor")),
+            ("base", Some("types")),
+            ("fields", Some("field_visitor")),
+            ("own_fields", Some("field_visitor")),
+            ("defaults", Some("field_visitor")),
+        ],
+    )
+    .flags(&[
+        "has_fields",
+        "has_doc",
+        "has_defaults",
+        "has_optional_fields",
+        "is_generic",
+        "has_base",
+        "has_own_fields",
+    ])
+    .target(AttributeTarget::Struct),
+    SectionSpec::new(
+        "enum_variant_visitor",
+        &[("name", None), ("doc", DOC), ("enum", None), ("val", None)],
+    )
+    .flags(&["is_int_enum", "has_doc"])
+    .target(AttributeTarget::Variant),
+    SectionSpec::new(
+        "message_enum",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("variants", Some("enum_variant_visitor")),
+        ],
+    )
+    .flags(&["is_int_enum", "has_doc"])
+    .target(AttributeTarget::Enum),
+    SectionSpec::new(
+        "type_alias",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("params", Some("text_visitor")),
+            ("T", Some("types")),
+        ],
+    )
+    .flags(&["is_nullable", "is_generic", "has_doc"])
+    .target(AttributeTarget::Alias),
+    SectionSpec::new(
+        "union_variant_visitor",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("union", None),
+            ("tag", None),
+            ("fields", Some("field_visitor")),
+            ("defaults", Some("field_visitor")),
+        ],
+    )
+    .flags(&[
+        "has_fields",
+        "has_doc",
+        "has_defaults",
+        "has_optional_fields",
+    ])
+    .target(AttributeTarget::Variant),
+    SectionSpec::new(
+        "message_union",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("tag", None),
+            ("variants", Some("union_variant_visitor")),
+            ("defaults", Some("field_visitor")),
+        ],
+    )
+    .flags(&["has_doc", "has_defaults"])
+    .target(AttributeTarget::Union),
+    SectionSpec::new(
+        "method_visitor",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("input", Some("types")),
+            ("output", Some("types")),
+        ],
+    )
+    .flags(&["has_doc"])
+    .target(AttributeTarget::Method),
+    SectionSpec::new(
+        "service",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("methods", Some("method_visitor")),
+        ],
+    )
+    .flags(&["has_doc"])
+    .target(AttributeTarget::Service),
+    SectionSpec::new(
+        "constant",
+        &[
+            ("name", None),
+            ("doc", DOC),
+            ("ty", Some("types")),
+            ("resolved_ty", Some("types")),
+            ("value", None),
+        ],
+    )
+    .flags(&["has_doc"])
+    .target(AttributeTarget::Constant),
+    SectionSpec::new("text_visitor", &[("value", None)]),
+    // Entries of the `#types` sections
+    SectionSpec::new("array", &[("T", Some("types"))]),
+    SectionSpec::new("map", &[("T", Some("types"))]),
+    SectionSpec::new("null", &[("T", Some("types"))]),
+    SectionSpec::new("tuple", &[("Ts", None)]),
+    SectionSpec::new("struct", &[("T", None)]),
+    SectionSpec::new("param", &[("T", None)]),
+    SectionSpec::new("generic", &[("T", None), ("args", None)]),
+    // Entries of the `#values` section
+    SectionSpec::new("value", &[("value", None)]),
+    SectionSpec::new("value_some", &[("T", None), ("ty", Some("types"))]),
+    SectionSpec::new("value_array", &[("items", None), ("ty", Some("types"))]),
+    SectionSpec::new("value_map", &[("entries", None), ("ty", Some("types"))]),
+    SectionSpec::new("value_entry", &[("key", None), ("value", None)]),
+    SectionSpec::new("value_variant", &[("enum", None), ("name", None)]),
+];
+
+/// The innermost `%for%` loop around the span being validated: its item and the section
+/// used to render each item
+type Loop<'t> = (&'t str, &'static str);
+
+impl<'t> Template<'t> {
+    /// Checks that every variable used by the template is available in its section, and
+    /// that the visitors and filters they ask for exist
+    fn validate(&self, source: &str) -> Result<(), TemplateError> {
+        let mut spans = vec![
+            ("prelude", "prelude", &self.prelude),
+            ("import", "import", &self.import),
+            ("field_visitor", "field_visitor", &self.field_body),
+            ("message_struct", "message_struct", &self.message_struct),
+            (
+                "enum_variant_visitor",
+                "enum_variant_visitor",
+                &self.enum_variant,
+            ),
+            ("message_enum", "message_enum", &self.message_enum),
+            ("type_alias", "type_alias", &self.type_alias),
+            (
+                "union_variant_visitor",
+                "union_variant_visitor",
+                &self.union_variant,
+            ),
+            ("message_union", "message_union", &self.message_union),
+            ("method_visitor", "method_visitor", &self.method_body),
+            ("service", "service", &self.service),
+            ("constant", "constant", &self.constant),
+        ];
+
+        for (name, span) in self.alternates.iter() {
+            let (base, _) = name.split_once('@').unwrap();
+            spans.push((name, base, span));
+        }
+
+        let tables = std::iter::once(&self.types).chain(self.alternate_types.values());
+        for table in tables {
+            for (entry, span) in table.entries() {
+                spans.push(("types", entry, span));
+            }
+        }
+
+        let tables = std::iter::once(&self.values).chain(self.alternate_values.values());
+        for table in tables {
+            for (kind, span) in table.entries() {
+                spans.push(("values", kind, span));
+            }
+        }
+
+        for (section, kind, span) in spans {
+            self.validate_span(source, section, kind, span, None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Validates a span of `section`, whose variables are those of `kind`. Inside a loop,
+    /// only the variables of the loop's item are available
+    fn validate_span(
+        &self,
+        source: &str,
+        section: &str,
+        kind: &str,
+        span: &TemplateSpan,
+        cur_loop: Option<Loop>,
+    ) -> Result<(), TemplateError> {
+        let resolve = |name| resolve_name(kind, cur_loop, name);
+
+        for inst in span.instructions.iter() {
+            match inst {
+                Instruction::Newline | Instruction::Indent(_) | Instruction::Literal(_) => {}
+                Instruction::Expand { var, opts } => {
+                    self.validate_variable(source, section, resolve(var), var, opts)?;
+                }
+                Instruction::If {
+                    cond,
+                    then,
+                    otherwise,
+                } => {
+                    let known = match resolve(cond.flag) {
+                        Some((kind, flag)) => SectionSpec::of(kind).has_flag(flag),
+                        None => false,
+                    };
+
+                    if !known {
+                        return Err(TemplateError::at(
+                            source,
+                            cond.flag,
+                            TemplateErrorKind::UnknownFlag {
+                                flag: cond.flag.to_string(),
+                                section: section.to_string(),
+                            },
+                        ));
+                    }
+
+                    self.validate_span(source, section, kind, then, cur_loop)?;
+                    self.validate_span(source, section, kind, otherwise, cur_loop)?;
+                }
+                Instruction::For {
+                    item,
+                    var,
+                    opts,
+                    body,
+                    ..
+                } => {
+                    let visitor_base =
+                        self.validate_variable(source, section, resolve(var), var, opts)?;
+
+                    // Types and values are rendered from a table rather than a single section
+                    let item_kind = match visitor_base {
+                        Some(base) if base != "types" && base != "values" => base,
+                        _ => {
+                            return Err(TemplateError::at(
+                                source,
+                                var,
+                                TemplateErrorKind::NotIterable(var.to_string()),
+                            ))
+                        }
+                    };
+
+                    self.validate_span(source, section, kind, body, Some((item, item_kind)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a single use of a variable, given as its (kind, name) after resolving
+    /// loop items. Returns the section whose alternates can render the variable
+    fn validate_variable(
+        &self,
+        source: &str,
+        section: &str,
+        resolved: Option<(&str, &str)>,
+        var: &str,
+        opts: &ExpandOptions,
+    ) -> Result<Option<&'static str>, TemplateError> {
+        let error = |kind| Err(TemplateError::at(source, var, kind));
+
+        let variable = resolved.and_then(|(kind, name)| SectionSpec::of(kind).variable(name));
+
+        let Some(visitor_base) = variable else {
+            return error(TemplateErrorKind::UnknownVariable {
+                var: var.to_string(),
+                section: section.to_string(),
+            });
+        };
+
+        if let Some(visitor) = &opts.visitor {
+            let exists = match visitor_base {
+                None => return error(TemplateErrorKind::UnexpectedVisitor(var.to_string())),
+                Some("types") => self.alternate_types.contains_key(visitor.as_str()),
+                Some("values") => self.alternate_values.contains_key(visitor.as_str()),
+                Some(base) => self
+                    .alternates
+                    .contains_key(format!("{}@{}", base, visitor).as_str()),
+            };
+
+            if !exists {
+                let name = format!("{}@{}", visitor_base.unwrap(), visitor);
+                return error(TemplateErrorKind::UnknownSection(name));
+            }
+        }
+
+        if let Some(names) = &opts.filter {
+            if let Some(name) = names.split('|').find(|name| find_filter(name).is_none()) {
+                return error(TemplateErrorKind::UnknownFilter(name.to_string()));
+            }
+        }
+
+        Ok(visitor_base)
+    }
 }
 
 /* ======================= Utils ======================= */
 
-fn stream_parse_visitors<'t, F>(mut source: &'t str, mut receiver: F)
+/// Finds the kind of section a variable or flag named `name` belongs to, stripping the
+/// loop item's prefix off the name if it is used inside a loop
+fn resolve_name<'n>(
+    kind: &'n str,
+    cur_loop: Option<Loop<'n>>,
+    name: &'n str,
+) -> Option<(&'n str, &'n str)> {
+    match cur_loop {
+        None => Some((kind, name)),
+        Some((item, item_kind)) => match name.split_once('.') {
+            Some((prefix, name)) if prefix == item => Some((item_kind, name)),
+            _ => None,
+        },
+    }
+}
+
+/// Is `line` of the form `#name` or `#end/name` ? Other lines starting with a `#` (for
+/// example Rust attributes or C preprocessor directives) are part of the section body
+fn is_section_marker(line: &str) -> bool {
+    let name = match line.trim().strip_prefix('#') {
+        Some(rest) => rest.strip_prefix("end/").unwrap_or(rest),
+        None => return false,
+    };
+
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@')
+}
+
+/// Parses entries of the form `name { body }`. `template` is the whole template, which
+/// `source` is a part of
+fn stream_parse_visitors<'t, F>(
+    template: &'t str,
+    mut source: &'t str,
+    mut receiver: F,
+) -> Result<(), TemplateError>
 where
-    F: FnMut(&'t str, TemplateSpan<'t>),
+    F: FnMut(&'t str, TemplateSpan<'t>) -> Result<(), TemplateError>,
 {
     loop {
         source = source.trim();
@@ -256,9 +1454,18 @@ where
             break;
         }
 
-        let (name, rem) = source.split_at(source.find(' ').unwrap());
+        let name_end = source
+            .find(|c: char| c.is_whitespace() || c == '{')
+            .unwrap_or(source.len());
+
+        let (name, rem) = source.split_at(name_end);
 
-        let rem = rem.trim_start().strip_prefix('{').unwrap();
+        let error = |kind| TemplateError::at(template, name, kind);
+
+        let rem = rem
+            .trim_start()
+            .strip_prefix('{')
+            .ok_or_else(|| error(TemplateErrorKind::MissingTypeBody(name.to_string())))?;
 
         // at this point rem is something like this
         // ..target string..}..extra string...
@@ -278,11 +1485,16 @@ where
             }
         }
 
-        let (body, rem) = rem.split_at(end_index.unwrap());
+        let end_index = end_index
+            .ok_or_else(|| error(TemplateErrorKind::UnclosedTypeBody(name.to_string())))?;
+
+        let (body, rem) = rem.split_at(end_index);
 
-        receiver(name, compile_span(body.trim()));
+        receiver(name, compile_span(template, body.trim())?)?;
 
         // Remove the trailing (or now, leading) closing bracket after the parsed body
-        source = rem.strip_prefix('}').unwrap()
+        source = &rem[1..];
     }
+
+    Ok(())
 }
@@ -1,9 +1,88 @@
 #[derive(Debug, Clone)]
 pub enum PrimitiveType {
     String,
+    /// `int` or `i32`
     Int,
+    /// `float` or `f64`
     Float,
     Bool,
+    I8,
+    I16,
+    /// Sent as a string, since JSON numbers can not hold every 64 bit integer
+    I64,
+    U8,
+    U16,
+    U32,
+    /// Sent as a string, like `I64`
+    U64,
+    F32,
+    /// Binary data, sent as a base64 string
+    Bytes,
+    /// An instant, sent as an RFC 3339 string (`2024-05-01T12:30:00Z`)
+    Timestamp,
+    /// A calendar date, sent as a `YYYY-MM-DD` string
+    Date,
+    /// A span of time, sent as a number of seconds
+    Duration,
+    /// Sent as a hyphenated string
+    Uuid,
+    /// A decimal number, sent as a string (`-12.50`) to keep its exact value
+    Decimal,
+    /// Sent as a string
+    Url,
+}
+
+impl PrimitiveType {
+    /// Returns the primitive type named `name` in schemas
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "string" => PrimitiveType::String,
+            "int" | "i32" => PrimitiveType::Int,
+            "float" | "f64" => PrimitiveType::Float,
+            "bool" => PrimitiveType::Bool,
+            "i8" => PrimitiveType::I8,
+            "i16" => PrimitiveType::I16,
+            "i64" => PrimitiveType::I64,
+            "u8" => PrimitiveType::U8,
+            "u16" => PrimitiveType::U16,
+            "u32" => PrimitiveType::U32,
+            "u64" => PrimitiveType::U64,
+            "f32" => PrimitiveType::F32,
+            "bytes" => PrimitiveType::Bytes,
+            "timestamp" => PrimitiveType::Timestamp,
+            "date" => PrimitiveType::Date,
+            "duration" => PrimitiveType::Duration,
+            "uuid" => PrimitiveType::Uuid,
+            "decimal" => PrimitiveType::Decimal,
+            "url" => PrimitiveType::Url,
+            _ => return None,
+        })
+    }
+
+    /// The name of the type in schemas
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrimitiveType::String => "string",
+            PrimitiveType::Int => "int",
+            PrimitiveType::Float => "float",
+            PrimitiveType::Bool => "bool",
+            PrimitiveType::I8 => "i8",
+            PrimitiveType::I16 => "i16",
+            PrimitiveType::I64 => "i64",
+            PrimitiveType::U8 => "u8",
+            PrimitiveType::U16 => "u16",
+            PrimitiveType::U32 => "u32",
+            PrimitiveType::U64 => "u64",
+            PrimitiveType::F32 => "f32",
+            PrimitiveType::Bytes => "bytes",
+            PrimitiveType::Timestamp => "timestamp",
+            PrimitiveType::Date => "date",
+            PrimitiveType::Duration => "duration",
+            PrimitiveType::Uuid => "uuid",
+            PrimitiveType::Decimal => "decimal",
+            PrimitiveType::Url => "url",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -14,6 +93,12 @@ pub enum TyKind {
     // User Defined
     UserDefined(String),
 
+    // A generic user defined type given its type arguments, Page<T>
+    Generic(String, Vec<TyKind>),
+
+    // A type parameter of the generic type being defined
+    Param(String),
+
     // The nullable type T?
     Nullable(Box<TyKind>),
 
@@ -27,16 +112,319 @@ pub enum TyKind {
     Tuple(Vec<TyKind>),
 }
 
+impl TyKind {
+    /// Appends the names of all the user-defined types referenced by this type to `out`
+    pub fn collect_udts<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            TyKind::Primitive(..) | TyKind::Param(..) => {}
+            TyKind::UserDefined(name) => out.push(name),
+            TyKind::Generic(name, args) => {
+                out.push(name);
+                for ty in args.iter() {
+                    ty.collect_udts(out);
+                }
+            }
+            TyKind::Nullable(inner) | TyKind::Array(inner) | TyKind::Map(inner) => {
+                inner.collect_udts(out)
+            }
+            TyKind::Tuple(inner_tys) => {
+                for ty in inner_tys.iter() {
+                    ty.collect_udts(out);
+                }
+            }
+        }
+    }
+
+    /// Does this type, or any type nested inside it, satisfy `pred` ?
+    pub fn any(&self, pred: &impl Fn(&TyKind) -> bool) -> bool {
+        pred(self)
+            || match self {
+                TyKind::Primitive(..) | TyKind::UserDefined(..) | TyKind::Param(..) => false,
+                TyKind::Nullable(inner) | TyKind::Array(inner) | TyKind::Map(inner) => {
+                    inner.any(pred)
+                }
+                TyKind::Tuple(inner_tys) | TyKind::Generic(_, inner_tys) => {
+                    inner_tys.iter().any(|ty| ty.any(pred))
+                }
+            }
+    }
+
+    /// Replaces the type parameters named in `params` by the matching type of `args`
+    pub fn substitute(&self, params: &[String], args: &[TyKind]) -> TyKind {
+        let map = |ty: &TyKind| Box::new(ty.substitute(params, args));
+
+        match self {
+            TyKind::Param(name) => match params.iter().position(|param| param == name) {
+                Some(index) if index < args.len() => args[index].clone(),
+                _ => self.clone(),
+            },
+            TyKind::Primitive(..) | TyKind::UserDefined(..) => self.clone(),
+            TyKind::Nullable(inner) => TyKind::Nullable(map(inner)),
+            TyKind::Array(inner) => TyKind::Array(map(inner)),
+            TyKind::Map(inner) => TyKind::Map(map(inner)),
+            TyKind::Tuple(inner_tys) => {
+                TyKind::Tuple(inner_tys.iter().map(|ty| *map(ty)).collect())
+            }
+            TyKind::Generic(name, inner_tys) => {
+                TyKind::Generic(name.clone(), inner_tys.iter().map(|ty| *map(ty)).collect())
+            }
+        }
+    }
+}
+
+/// Writes `items` separated by commas
+fn write_list(f: &mut std::fmt::Formatter<'_>, items: &[TyKind]) -> std::fmt::Result {
+    for (i, ty) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", ty)?;
+    }
+    Ok(())
+}
+
+/// Writes the type as it is written in schemas
+impl std::fmt::Display for TyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TyKind::Primitive(prim) => f.write_str(prim.name()),
+            TyKind::UserDefined(name) => f.write_str(name),
+            TyKind::Nullable(inner) => write!(f, "{}?", inner),
+            TyKind::Array(inner) => write!(f, "[{}]", inner),
+            TyKind::Map(inner) => write!(f, "<{}>", inner),
+            TyKind::Param(name) => f.write_str(name),
+            TyKind::Generic(name, args) => {
+                write!(f, "{}<", name)?;
+                write_list(f, args)?;
+                f.write_str(">")
+            }
+            TyKind::Tuple(inner_tys) => {
+                f.write_str("(")?;
+                write_list(f, inner_tys)?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/* ==================================== */
+
+/// A value written in a schema, such as the default value of a field
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Wide enough for any `i64` or `u64`, the range of the actual type is checked by the
+    /// parser
+    Int(i128),
+    Float(f64),
+    String(String),
+    Bool(bool),
+    Null,
+    Array(Vec<Value>),
+    /// `(value, ...)`, the value of a tuple type
+    Tuple(Vec<Value>),
+    /// `{ "key": value, ... }`
+    Map(Vec<(String, Value)>),
+    /// A variant of an enum, `Mode.Fast`
+    Variant {
+        enum_name: String,
+        variant: String,
+    },
+}
+
+/// The default value of a field
+#[derive(Debug, Clone)]
+pub struct DefaultValue {
+    pub value: Value,
+    /// The type of the field with type aliases replaced by the types they stand for,
+    /// so that generators know how to write `value`
+    pub ty: TyKind,
+}
+
+/* ==================================== */
+
+/// An argument of an attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+}
+
+impl std::fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeValue::String(val) => f.write_str(val),
+            AttributeValue::Int(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+/// Metadata attached to an item, field or variant, e.g `@since(2)`
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<AttributeValue>,
+}
+
+impl Attribute {
+    /// The first argument of the attribute, if any
+    pub fn value(&self) -> Option<&AttributeValue> {
+        self.args.first()
+    }
+}
+
+/// Returns the attribute named `name` in `attributes`, if present
+pub fn find_attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attributes.iter().find(|attr| attr.name == name)
+}
+
+/// What an attribute is written on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeTarget {
+    Struct,
+    Field,
+    Enum,
+    /// A variant of an enum or a union
+    Variant,
+    Alias,
+    Union,
+    Service,
+    Method,
+    Constant,
+}
+
+impl std::fmt::Display for AttributeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AttributeTarget::Struct => "struct",
+            AttributeTarget::Field => "field",
+            AttributeTarget::Enum => "enum",
+            AttributeTarget::Variant => "variant",
+            AttributeTarget::Alias => "type alias",
+            AttributeTarget::Union => "union",
+            AttributeTarget::Service => "service",
+            AttributeTarget::Method => "method",
+            AttributeTarget::Constant => "constant",
+        })
+    }
+}
+
+/// The kind of value an argument of an attribute takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeParam {
+    String,
+    Int,
+}
+
+/// Describes an attribute that schemas may use
+#[derive(Debug)]
+pub struct AttributeSpec {
+    pub name: &'static str,
+    pub params: &'static [AttributeParam],
+    /// How many of the `params` must be given, the rest are optional
+    pub required: usize,
+    pub targets: &'static [AttributeTarget],
+}
+
+impl AttributeSpec {
+    pub fn applies_to(&self, target: AttributeTarget) -> bool {
+        self.targets.contains(&target)
+    }
+}
+
+const ALL_TARGETS: &[AttributeTarget] = &[
+    AttributeTarget::Struct,
+    AttributeTarget::Field,
+    AttributeTarget::Enum,
+    AttributeTarget::Variant,
+    AttributeTarget::Alias,
+    AttributeTarget::Union,
+    AttributeTarget::Service,
+    AttributeTarget::Method,
+    AttributeTarget::Constant,
+];
+
+/// Every attribute that schemas may use
+pub const ATTRIBUTES: &[AttributeSpec] = &[
+    // @deprecated or @deprecated("use x instead")
+    AttributeSpec {
+        name: "deprecated",
+        params: &[AttributeParam::String],
+        required: 0,
+        targets: ALL_TARGETS,
+    },
+    // @since(2), the version of the schema that introduced the target
+    AttributeSpec {
+        name: "since",
+        params: &[AttributeParam::Int],
+        required: 1,
+        targets: ALL_TARGETS,
+    },
+    // @rename("access_token"), the name of the field on the wire
+    AttributeSpec {
+        name: "rename",
+        params: &[AttributeParam::String],
+        required: 1,
+        targets: &[AttributeTarget::Field],
+    },
+];
+
+/// Returns the known attribute named `name`
+pub fn attribute_spec(name: &str) -> Option<&'static AttributeSpec> {
+    ATTRIBUTES.iter().find(|spec| spec.name == name)
+}
+
+/* ==================================== */
+
 #[derive(Debug, Clone)]
 pub struct StructField {
     pub datatype: TyKind,
     pub name: String,
+    /// Lines of the `///` comments written before the field
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the field
+    pub attributes: Vec<Attribute>,
+    /// Can the field be absent from serialized data (`name?: T`) ? This is unrelated to
+    /// the field being nullable (`name: T?`), which only allows it to be `null`
+    pub optional: bool,
+    /// The value used when the field is missing from serialized data
+    pub default: Option<DefaultValue>,
+}
+
+impl StructField {
+    /// The key of the field in serialized data. This is `name` unless the field is given
+    /// a `@rename`, `name` is then only used for identifiers in generated code
+    pub fn wire_name(&self) -> &str {
+        match find_attribute(&self.attributes, "rename").and_then(Attribute::value) {
+            Some(AttributeValue::String(name)) => name,
+            _ => &self.name,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct StructDefinition {
     pub name: String,
+    /// Names of the type parameters, `T` in `struct Page<T>`
+    pub params: Vec<String>,
+    /// The struct this one extends, `Entity` in `struct User extends Entity`
+    pub base: Option<TyKind>,
+    /// Fields declared in the struct itself
     pub fields: Vec<StructField>,
+    /// Fields that come from the struct being extended (and the ones it extends), with
+    /// the type arguments given to it substituted. These are resolved once the whole
+    /// file is parsed
+    pub inherited: Vec<StructField>,
+    /// Lines of the `///` comments written before the struct
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the struct
+    pub attributes: Vec<Attribute>,
+}
+
+impl StructDefinition {
+    /// Every field of the struct, the inherited ones first
+    pub fn all_fields(&self) -> impl Iterator<Item = &StructField> + Clone {
+        self.inherited.iter().chain(self.fields.iter())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,23 +437,222 @@ pub enum EnumVariantValue {
 pub struct EnumVariant {
     pub name: String,
     pub value: EnumVariantValue,
+    /// Lines of the `///` comments written before the variant
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the variant
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
 pub struct EnumDefinition {
     pub name: String,
     pub variants: Vec<EnumVariant>,
+    /// Lines of the `///` comments written before the enum
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the enum
+    pub attributes: Vec<Attribute>,
+}
+
+impl EnumDefinition {
+    /// Are the variants of this enum represented by integers (rather than strings) ?
+    pub fn is_int_enum(&self) -> bool {
+        // All the variants share the same kind of value
+        !matches!(
+            self.variants.first(),
+            Some(EnumVariant {
+                value: EnumVariantValue::String(..),
+                ..
+            })
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TypeAlias {
+    pub name: String,
+    /// Names of the type parameters, `T` and `E` in `type Result<T, E>`
+    pub params: Vec<String>,
+    pub typ: TyKind,
+    /// Lines of the `///` comments written before the alias
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the alias
+    pub attributes: Vec<Attribute>,
+}
+
+/// Name of the field that carries the variant name of a union value on the wire
+pub const UNION_TAG: &str = "kind";
+
+#[derive(Debug, Clone)]
+pub struct UnionVariant {
+    pub name: String,
+    pub fields: Vec<StructField>,
+    /// Lines of the `///` comments written before the variant
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the variant
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnionDefinition {
+    pub name: String,
+    pub variants: Vec<UnionVariant>,
+    /// Lines of the `///` comments written before the union
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the union
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceMethod {
+    pub name: String,
+    pub input: TyKind,
+    pub output: TyKind,
+    /// Lines of the `///` comments written before the method
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the method
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceDefinition {
+    pub name: String,
+    pub methods: Vec<ServiceMethod>,
+    /// Lines of the `///` comments written before the service
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the service
+    pub attributes: Vec<Attribute>,
+}
+
+/// A named value shared by every target, `const MAX_PAGE_SIZE: int = 100;`
+#[derive(Debug, Clone)]
+pub struct ConstantDefinition {
     pub name: String,
     pub typ: TyKind,
+    pub value: Value,
+    /// `typ` with type aliases replaced by the types they stand for, so that generators
+    /// know how to write `value`
+    pub resolved: TyKind,
+    /// Lines of the `///` comments written before the constant
+    pub doc: Vec<String>,
+    /// Attributes (`@name(...)`) written before the constant
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// Path of the imported file, relative to the importing file
+    pub path: String,
+    /// Names of the imported types
+    pub names: Vec<String>,
+    /// Name of the file generated for the imported file, without its extension. Generated
+    /// files all go in the same directory, so this is all it takes to import it. It is the
+    /// file name of `path` until [`crate::language::link_imports`] sets the actual one
+    pub module: String,
+}
+
+impl Import {
+    pub fn new(path: String, names: Vec<String>) -> Self {
+        let module = std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            names,
+            module,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Item {
+    Struct(StructDefinition),
+    Enum(EnumDefinition),
+    TypeAlias(TypeAlias),
+    Union(UnionDefinition),
+    Service(ServiceDefinition),
+    Constant(ConstantDefinition),
+}
+
+impl Item {
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Struct(struct_) => &struct_.name,
+            Item::Enum(enum_) => &enum_.name,
+            Item::TypeAlias(alias) => &alias.name,
+            Item::Union(union_) => &union_.name,
+            Item::Service(service) => &service.name,
+            Item::Constant(constant) => &constant.name,
+        }
+    }
+
+    /// Does this item define a type that can be referenced by other items ?
+    pub fn is_type(&self) -> bool {
+        !matches!(self, Item::Service(..) | Item::Constant(..))
+    }
+
+    /// Type parameters of a generic struct or type alias, empty for other items
+    pub fn type_params(&self) -> &[String] {
+        match self {
+            Item::Struct(struct_) => &struct_.params,
+            Item::TypeAlias(alias) => &alias.params,
+            _ => &[],
+        }
+    }
+
+    /// The types this item is directly made of, e.g the types of a struct's fields
+    pub fn types(&self) -> Vec<&TyKind> {
+        match self {
+            Item::Struct(struct_) => struct_
+                .base
+                .iter()
+                .chain(struct_.fields.iter().map(|f| &f.datatype))
+                .collect(),
+            Item::Enum(..) => vec![],
+            Item::TypeAlias(alias) => vec![&alias.typ],
+            Item::Union(union_) => union_
+                .variants
+                .iter()
+                .flat_map(|v| v.fields.iter())
+                .map(|f| &f.datatype)
+                .collect(),
+            Item::Service(service) => service
+                .methods
+                .iter()
+                .flat_map(|m| [&m.input, &m.output])
+                .collect(),
+            Item::Constant(constant) => vec![&constant.typ],
+        }
+    }
+
+    /// Names of the user-defined types referenced by this item
+    pub fn dependencies(&self) -> Vec<&str> {
+        let mut deps = vec![];
+
+        for ty in self.types() {
+            ty.collect_udts(&mut deps);
+        }
+
+        deps
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Program {
-    pub structs: Vec<StructDefinition>,
-    pub enums: Vec<EnumDefinition>,
-    pub type_aliases: Vec<TypeAlias>,
+    pub imports: Vec<Import>,
+    /// All the items defined in the program. Items come in declaration order, except
+    /// that every item is placed after the items it depends upon (when possible)
+    pub items: Vec<Item>,
+}
+
+impl Program {
+    /// Names of all the types defined (not imported) in this program, along with their
+    /// number of type parameters
+    pub fn type_arities(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.items
+            .iter()
+            .filter(|item| item.is_type())
+            .map(|item| (item.name(), item.type_params().len()))
+    }
 }
@@ -2,7 +2,56 @@ use crate::items::Program;
 use std::path::{Path, PathBuf};
 
 pub trait Language: Sync {
-    fn lang_id(&self) -> &'static str;
-    fn extension(&self) -> &'static str;
+    fn lang_id(&self) -> &str;
+    fn extension(&self) -> &str;
     fn generate(&self, program: &Program, outfile: &Path);
+
+    /// The name of the file generated from the schema named `stem`, which it is also
+    /// imported by. Languages that import files as modules make it a valid identifier
+    fn module_name(&self, stem: &str) -> String {
+        stem.to_string()
+    }
+}
+
+/// The name of the file `lang` generates from the schema at `schema`. In `pattern`,
+/// `[name]` stands for the schema's file name and `[ext]` for the language's extension
+pub fn output_filename(lang: &dyn Language, pattern: &str, schema: &Path) -> String {
+    let stem = schema
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    pattern
+        .replace("[name]", &lang.module_name(&stem))
+        .replace("[ext]", lang.extension())
+}
+
+/// Points the imports of `program` at the files `lang` generates for them with `pattern`,
+/// see [`output_filename`]
+pub fn link_imports(lang: &dyn Language, pattern: &str, program: &mut Program) {
+    for import in program.imports.iter_mut() {
+        let filename = output_filename(lang, pattern, Path::new(&import.path));
+        let extension = format!(".{}", lang.extension());
+
+        import.module = match filename.strip_suffix(&extension) {
+            Some(module) => module.to_string(),
+            None => filename,
+        };
+    }
+}
+
+/// `my-common` => `my_common`, a name that can be imported as a module
+pub fn identifier(stem: &str) -> String {
+    let name = stem
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect::<String>();
+
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{}", name),
+        false => name,
+    }
 }
@@ -28,6 +28,10 @@ impl ForserFile {
         Ok(Self { contents })
     }
 
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
     pub fn source(&self) -> impl Source + '_ {
         FileSource {
             chars: self.contents.chars(),
@@ -41,10 +45,13 @@ pub struct Lexer<'a, S> {
     source: &'a mut S,
     current: Option<char>,
     next: Option<char>,
-    last_pos: i64,
     position: i64, // Position of next character
     column: i64,
     line: i64,
+    // Location of the first character of the token being lexed
+    token_pos: i64,
+    token_column: i64,
+    token_line: i64,
 }
 
 impl<'a, S> Lexer<'a, S>
@@ -56,15 +63,17 @@ where
             current: None,
             next: source.next_char(),
             source,
-            last_pos: 1,
             position: 0,
             column: 0,
             line: 1,
+            token_pos: 0,
+            token_column: 0,
+            token_line: 1,
         }
     }
 
     fn consume(&mut self) -> Option<char> {
-        self.current = self.next.clone();
+        self.current = self.next;
         self.next = self.source.next_char();
 
         if let Some(x) = self.current {
@@ -73,7 +82,7 @@ where
             // TODO: Convert all \r\n sequences to \n
 
             if x == '\n' {
-                self.column = 1;
+                self.column = 0;
                 self.line += 1;
             } else {
                 self.column += 1;
@@ -86,39 +95,62 @@ where
     }
 
     fn peek(&self) -> Option<char> {
-        self.next.clone()
+        self.next
+    }
+
+    /// Marks the current character as the start of the next token
+    fn mark_token_start(&mut self) {
+        self.token_pos = self.position - 1;
+        self.token_column = self.column;
+        self.token_line = self.line;
     }
 
     fn emit_token(&mut self, kind: TokenKind) -> Token {
-        let tok = Token {
+        Token {
             kind,
-            pos_start: self.last_pos,
+            pos_start: self.token_pos,
             pos_end: self.position,
-            column: self.column,
-            line: self.line,
-        };
-
-        self.last_pos = self.position;
-
-        tok
+            column: self.token_column,
+            line: self.token_line,
+        }
     }
 
     pub fn next_token(&mut self) -> Token {
         loop {
             let c = match self.consume() {
                 Some(x) => x,
-                None => return self.emit_token(TokenKind::Eof),
+                None => {
+                    self.token_pos = self.position;
+                    self.token_column = self.column + 1;
+                    self.token_line = self.line;
+                    return self.emit_token(TokenKind::Eof);
+                }
             };
 
-            if c == ' ' || c == '\n' {
+            if c.is_whitespace() {
                 continue;
             }
 
             if c == '/' && self.next.is_some_and(|c| c == '/') {
+                self.mark_token_start();
+                self.consume();
+
+                // `///` starts a doc comment, but `////...` is a regular comment
+                if self.next == Some('/') {
+                    self.consume();
+
+                    if self.next != Some('/') {
+                        let text = self.consume_doc_comment();
+                        return self.emit_token(TokenKind::DocComment(text));
+                    }
+                }
+
                 self.consume_line_comment();
                 continue;
             }
 
+            self.mark_token_start();
+
             let token_kind = match c {
                 '(' => TokenKind::ParenLeft,
                 ')' => TokenKind::ParenRight,
@@ -131,17 +163,23 @@ where
                 ',' => TokenKind::Comma,
                 ':' => TokenKind::Colon,
                 ';' => TokenKind::Semicolon,
+                '.' => TokenKind::Dot,
                 '=' => TokenKind::Equal,
                 '?' => TokenKind::QuestionMark,
+                '@' => TokenKind::At,
+                '-' if self.next.is_some_and(|c| c == '>') => {
+                    self.consume();
+                    TokenKind::Arrow
+                }
+                '-' if self.next.is_some_and(|c| c.is_ascii_digit()) => {
+                    self.consume();
+                    self.consume_lit_number(true)
+                }
                 'a'..='z' | 'A'..='Z' => {
                     let ident: String = self.consume_identifier();
-                    token::to_keyword(&ident).unwrap_or_else(|| TokenKind::Identifier(ident))
-                }
-                // TODO: support negative numbers
-                '0'..='9' => {
-                    let val = self.cosume_lit_int();
-                    TokenKind::IntLiteral(val)
+                    token::to_keyword(&ident).unwrap_or(TokenKind::Identifier(ident))
                 }
+                '0'..='9' => self.consume_lit_number(false),
                 '"' => {
                     let val = self.cosume_lit_str();
                     TokenKind::StringLiteral(val)
@@ -155,12 +193,20 @@ where
     }
 
     fn consume_line_comment(&mut self) {
-        loop {
-            match self.consume() {
-                Some('\n') => break,
-                _ => (),
-            }
+        while !matches!(self.consume(), Some('\n') | None) {}
+    }
+
+    /// Consumes the rest of the line, without the space usually written after `///`
+    fn consume_doc_comment(&mut self) -> String {
+        let mut text = String::new();
+
+        while let Some(c) = self.next.filter(|c| *c != '\n') {
+            text.push(c);
+            self.consume();
         }
+
+        let text = text.strip_prefix(' ').unwrap_or(&text);
+        text.trim_end().to_string()
     }
 
     fn consume_identifier(&mut self) -> String {
@@ -174,23 +220,50 @@ where
         ident
     }
 
-    fn cosume_lit_int(&mut self) -> i32 {
-        let mut val: i32 = 0;
+    /// Consumes an integer or float literal, starting at the current digit. Integers are
+    /// read into an i128, which holds both the i64 and u64 ranges. Those too large even for
+    /// it saturate, out of range of every integer type they are then rejected as such
+    fn consume_lit_number(&mut self, negative: bool) -> TokenKind {
+        let mut text = String::new();
+        if negative {
+            text.push('-');
+        }
+        text.push(self.current.unwrap());
+        self.consume_digits(&mut text);
 
-        loop {
-            let d = self.current.and_then(|c| c.to_digit(10)).unwrap();
-            let d = i32::try_from(d).unwrap();
+        let mut is_float = false;
 
-            val = val * 10 + d;
+        if self.next == Some('.') {
+            self.consume();
+            text.push('.');
+            self.consume_digits(&mut text);
+            is_float = true;
+        }
 
-            if let Some(ds @ '0'..='9') = self.next {
+        if let Some(e @ ('e' | 'E')) = self.next {
+            self.consume();
+            text.push(e);
+            if let Some(sign @ ('+' | '-')) = self.next {
                 self.consume();
-            } else {
-                break;
+                text.push(sign);
             }
+            self.consume_digits(&mut text);
+            is_float = true;
         }
 
-        val
+        if is_float {
+            TokenKind::FloatLiteral(text)
+        } else {
+            let saturated = if negative { i128::MIN } else { i128::MAX };
+            TokenKind::IntLiteral(text.parse().unwrap_or(saturated))
+        }
+    }
+
+    fn consume_digits(&mut self, text: &mut String) {
+        while let Some(d @ '0'..='9') = self.next {
+            text.push(d);
+            self.consume();
+        }
     }
 
     fn cosume_lit_str(&mut self) -> String {
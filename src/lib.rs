@@ -2,10 +2,12 @@
 #![allow(unused_variables)]
 #![allow(unused_mut)]
 
+pub mod diagnostic;
 pub mod generators;
 pub mod glang;
 pub mod items;
 pub mod language;
 pub mod lexer;
 pub mod parser;
+pub mod resolver;
 pub mod token;
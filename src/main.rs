@@ -9,11 +9,14 @@ use std::process::ExitCode;
 use clap::Parser as ClapParser;
 use lazy_static::lazy_static;
 
+use forser::diagnostic;
+use forser::generators::TemplateGenerator;
 use forser::items::Program;
-use forser::language::Language;
+use forser::language::{link_imports, output_filename, Language};
 use forser::lexer::ForserFile;
 use forser::lexer::Lexer;
 use forser::parser::{ParseError, Parser};
+use forser::resolver::{LoadError, Resolver};
 
 #[derive(ClapParser, Debug)]
 #[command(version, about, long_about = None)]
@@ -29,7 +32,7 @@ struct Args {
     /// Comma separated list of target languages
     #[clap(
         short, long,
-        required = true,
+        required_unless_present = "template",
         value_parser = clap::builder::NonEmptyStringValueParser::new(),
         require_equals = true,
         num_args = 1..,
@@ -52,35 +55,65 @@ struct Args {
     /// `[ext]` is replaced by the standard extension of the generated language
     #[arg(short = 'f', long, default_value = "[name].[ext]")]
     out_filename: String,
+
+    /// Path to a custom `.gx` template to generate code with, in addition to `langs`
+    #[arg(long, requires = "template_ext")]
+    template: Option<PathBuf>,
+
+    /// Extension of the files generated from `template`
+    #[arg(long, requires = "template")]
+    template_ext: Option<String>,
+
+    /// Package of the generated Go files. Defaults to the name of the output directory,
+    /// or `schema` if that is not a valid package name
+    #[arg(long)]
+    go_package: Option<String>,
 }
 
+/// Creates the generator of a language, set up with the options given on the command line
+type Constructor = fn(&Args) -> Result<Box<dyn Language>, String>;
+
 lazy_static! {
-    static ref GENERATORS: HashMap<&'static str, Box<dyn Language>> = {
+    static ref GENERATORS: HashMap<&'static str, Constructor> = {
         use forser::generators::*;
-        let mut m: HashMap<&'static str, Box<dyn Language>> = HashMap::new();
-        m.insert("ts", Box::new(TypeScriptGenerator::new()));
-        m.insert("py", Box::new(PythonGenerator::new()));
+        let mut m: HashMap<&'static str, Constructor> = HashMap::new();
+        m.insert("ts", |_| Ok(Box::new(TypeScriptGenerator::new())));
+        m.insert("ts-decoders", |_| {
+            Ok(Box::new(TypeScriptGenerator::with_decoders()))
+        });
+        m.insert("py", |_| Ok(Box::new(PythonGenerator::new())));
+        m.insert("rs", |_| Ok(Box::new(RustGenerator::new())));
+        m.insert("go", |args| match &args.go_package {
+            Some(package) => match GoGenerator::with_package(package) {
+                Some(generator) => Ok(Box::new(generator)),
+                None => Err(format!("\"{}\" is not a valid Go package name", package)),
+            },
+            None => Ok(Box::new(GoGenerator::new())),
+        });
+        m.insert("jsonschema", |_| Ok(Box::new(JsonSchemaGenerator::new())));
         m
     };
 }
 
-// TODO: optimise
-fn write_programs(args: &Args, tasks: Vec<(&PathBuf, Program)>) {
-    let generators = args
-        .langs
-        .iter()
-        .map(|lang| {
-            let generator = GENERATORS.get(lang.as_str());
-            generator.map(|bx| bx.as_ref()).ok_or(lang)
-        })
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap_or_else(|unknown_lang| {
-            panic!("Unknown language \"{}\"", unknown_lang);
-        });
+/// The options given on the command line that only apply to one language, along with
+/// that language
+fn language_options(args: &Args) -> Vec<(&'static str, &'static str)> {
+    let mut options = vec![];
+
+    if args.go_package.is_some() {
+        options.push(("--go-package", "go"));
+    }
+
+    options
+}
 
+// TODO: optimise
+fn write_programs(args: &Args, generators: &[Box<dyn Language>], tasks: Vec<(&PathBuf, Program)>) {
     let out_dir = PathBuf::from(&args.out_dir);
 
     for gen in generators {
+        let gen = gen.as_ref();
+
         // Append language id to final output path if lang_dir is true
         let mut out = if args.lang_dir {
             out_dir.join(gen.lang_id())
@@ -91,16 +124,12 @@ fn write_programs(args: &Args, tasks: Vec<(&PathBuf, Program)>) {
         std::fs::create_dir_all(&out).expect("Failed to create output directory");
 
         for (in_file, program) in tasks.iter() {
-            let in_file_name = in_file.file_stem().and_then(|p| p.to_str()).unwrap();
+            let filename = output_filename(gen, &args.out_filename, in_file);
 
-            let filename = args
-                .out_filename
-                .replace("[name]", in_file_name)
-                .replace("[ext]", gen.extension());
+            let mut program = program.clone();
+            link_imports(gen, &args.out_filename, &mut program);
 
-            let out = out.join(filename);
-
-            gen.generate(&program, &out);
+            gen.generate(&program, &out.join(filename));
         }
     }
 }
@@ -108,32 +137,85 @@ fn write_programs(args: &Args, tasks: Vec<(&PathBuf, Program)>) {
 fn main() -> ExitCode {
     let args = Args::parse();
 
-    let tasks = args
-        .in_files
-        .iter()
-        .map(|in_file| {
-            let file = ForserFile::new(&in_file).expect("Failed to open file");
-            let mut source = file.source();
-            let mut lex = Lexer::new(&mut source);
-
-            let mut parser = Parser::new(lex);
-            let task = parser
-                .parse()
-                .map(|program| (in_file, program))
-                .map_err(|errors| unsafe { errors.get_unchecked(0).clone() });
-
-            task
-        })
-        .collect::<Result<Vec<_>, _>>();
-
-    match tasks {
-        Ok(tasks) => {
-            write_programs(&args, tasks);
-            ExitCode::SUCCESS
+    let custom = match (&args.template, &args.template_ext) {
+        (Some(path), Some(ext)) => match TemplateGenerator::from_file(path, ext) {
+            Ok(generator) => Some(generator),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        _ => None,
+    };
+
+    let mut generators = vec![];
+
+    for lang in args.langs.iter() {
+        let Some(create) = GENERATORS.get(lang.as_str()) else {
+            eprintln!("error: Unknown language \"{}\"", lang);
+            return ExitCode::FAILURE;
+        };
+
+        match create(&args) {
+            Ok(generator) => generators.push(generator),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if let Some(custom) = custom {
+        generators.push(Box::new(custom));
+    }
+
+    for (option, lang) in language_options(&args) {
+        if !args.langs.iter().any(|l| l == lang) {
+            eprintln!(
+                "warning: `{}` is ignored, since no {} code is generated",
+                option, lang
+            );
+        }
+    }
+
+    // Shared by all the input files so that common imports are only parsed once
+    let mut resolver = Resolver::new();
+
+    let mut tasks = vec![];
+    let mut failed = false;
+
+    // Load every file before bailing out so that all the errors are reported in one go
+    for in_file in args.in_files.iter() {
+        match resolver.load(in_file) {
+            Ok(program) => tasks.push((in_file, program)),
+            Err(err @ LoadError::Io { .. }) => {
+                eprintln!("error: {}\n", err);
+                failed = true;
+            }
+            Err(LoadError::Invalid(_)) => failed = true,
         }
-        Err(err) => {
-            eprintln!("{}", err);
-            ExitCode::FAILURE
+    }
+
+    if failed {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let mut error_count = 0;
+
+        for failure in resolver.failures() {
+            let path = failure.path.strip_prefix(&cwd).unwrap_or(failure.path);
+
+            for error in failure.errors {
+                eprintln!("{}", diagnostic::render(path, failure.source, error));
+                error_count += 1;
+            }
         }
+
+        if error_count > 0 {
+            eprintln!("error: aborting due to {} error(s)", error_count);
+        }
+
+        return ExitCode::FAILURE;
     }
+
+    write_programs(&args, &generators, tasks);
+    ExitCode::SUCCESS
 }
@@ -1,27 +1,34 @@
+use crate::glang::filter::snake_case;
 use crate::items::{
-    EnumDefinition, EnumVariant, EnumVariantValue, PrimitiveType, Program, StructDefinition,
-    StructField, TyKind, TypeAlias,
+    attribute_spec, Attribute, AttributeParam, AttributeTarget, AttributeValue, ConstantDefinition,
+    DefaultValue, EnumDefinition, EnumVariant, EnumVariantValue, Import, Item, PrimitiveType,
+    Program, ServiceDefinition, ServiceMethod, StructDefinition, StructField, TyKind, TypeAlias,
+    UnionDefinition, UnionVariant, Value, UNION_TAG,
 };
 use crate::lexer::TokenStream;
-use crate::token::{Token, TokenKind};
+use crate::token::{Span, Token, TokenKind};
 use std::collections::{HashMap, HashSet};
 
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
-pub enum ParseError {
-    #[error("Expected token {expected:?}, found {found:?}")]
+pub enum ParseErrorKind {
+    #[error("{}", unexpected_token_message(.expected, .found))]
     UnexpectedToken {
-        expected: Option<TokenKind>,
-        found: Token,
+        /// Any of these tokens would have been valid
+        expected: Vec<TokenKind>,
+        found: TokenKind,
     },
 
-    #[error("\"{0}\"")]
+    #[error("{0}")]
     Custom(String),
 
     #[error("Recursive Type \"{0}\" has infinite size")]
     RecursiveType(String),
 
+    #[error("Type alias \"{0}\" refers to itself")]
+    RecursiveAlias(String),
+
     #[error("Unknown Type \"{0}\"")]
     UnknownType(String),
 
@@ -30,22 +37,183 @@ pub enum ParseError {
 
     #[error("Type \"{0}\" is already defined")]
     RedefinedType(String),
+
+    #[error("Service \"{0}\" is already defined")]
+    RedefinedService(String),
+
+    #[error("Method \"{method}\" is already defined in service \"{service}\"")]
+    RedefinedMethod { service: String, method: String },
+
+    #[error("Failed to import \"{path}\": {reason}")]
+    ImportFailed { path: String, reason: String },
+
+    #[error("Import cycle detected: {0}")]
+    ImportCycle(String),
+
+    #[error("Type \"{name}\" is not defined in \"{path}\"")]
+    UnknownImport { name: String, path: String },
+
+    #[error("Unknown Attribute \"{0}\"")]
+    UnknownAttribute(String),
+
+    #[error("Attribute \"{name}\" can not be used on a {target}")]
+    MisplacedAttribute {
+        name: String,
+        target: AttributeTarget,
+    },
+
+    #[error("Attribute \"{0}\" is given more than once")]
+    RepeatedAttribute(String),
+
+    #[error("Key \"{0}\" is given more than once")]
+    RepeatedKey(String),
+
+    #[error("Invalid arguments, expected {0}")]
+    InvalidAttributeArgs(String),
+
+    #[error("Expected a default value of type \"{0}\"")]
+    InvalidDefault(String),
+
+    #[error("Expected a value of type \"{0}\"")]
+    InvalidConstant(String),
+
+    #[error("Constant \"{0}\" is already defined")]
+    RedefinedConstant(String),
+
+    #[error("Field \"{name}\" is already defined in \"{owner}\"")]
+    RedefinedField { name: String, owner: String },
+
+    #[error("Field \"{name}\" is sent as \"{wire}\", like field \"{other}\"")]
+    RepeatedWireName {
+        name: String,
+        wire: String,
+        other: String,
+    },
+
+    #[error("Invalid name on the wire {0:?}, it can not be empty nor hold quotes, backslashes, commas or control characters")]
+    InvalidWireName(String),
+
+    #[error("Field \"{name}\" has the same snake_case name as \"{other}\"")]
+    CollidingField { name: String, other: String },
+
+    #[error("Integer `{0}` is out of range")]
+    IntOutOfRange(String),
+
+    #[error("Type \"{name}\" takes {expected} type argument(s), found {found}")]
+    TypeArgCount {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Describes a token that was expected. Literals stand for any literal of their kind
+fn describe_expected(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::StringLiteral(_) => "string literal".to_string(),
+        TokenKind::IntLiteral(_) => "integer".to_string(),
+        TokenKind::FloatLiteral(_) => "float".to_string(),
+        TokenKind::Identifier(ident) if !ident.is_empty() => format!("`{}`", ident),
+        kind => kind.to_string(),
+    }
+}
+
+fn unexpected_token_message(expected: &[TokenKind], found: &TokenKind) -> String {
+    match expected {
+        [] => format!("Unexpected {}", found),
+        [expected] => format!("Expected {}, found {}", describe_expected(expected), found),
+        [init @ .., last] => {
+            let init = init
+                .iter()
+                .map(describe_expected)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "Expected {} or {}, found {}",
+                init,
+                describe_expected(last),
+                found
+            )
+        }
+    }
+}
+
+/// An error along with the region of the source file it originates from
+#[derive(Error, Debug, Clone)]
+#[error("{kind}")]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Span,
+}
+
+/// Loads the files referenced by `import` statements
+pub trait ImportResolver {
+    /// Resolves the file at `path` (as written in the import statement) and returns the
+    /// names of the types it defines, along with their number of type parameters
+    fn resolve(&mut self, path: &str) -> Result<Vec<(String, usize)>, ParseErrorKind>;
+}
+
+/// Doc comments and attributes written before an item, field or variant
+struct Prefix {
+    doc: Vec<String>,
+    attributes: Vec<(Attribute, Span)>,
 }
 
-pub struct Parser<L> {
+/// Returned by the parsing functions after a syntax error has been reported. The caller
+/// is expected to skip ahead to a known synchronisation point before continuing
+struct Desync;
+
+type PResult<T> = Result<T, Desync>;
+
+/// A value along with its declared type, and the error to report if they do not match
+type PendingValue = (TyKind, Value, Span, fn(String) -> ParseErrorKind);
+
+pub struct Parser<'r, L> {
     lexer: L,
+    resolver: Option<&'r mut dyn ImportResolver>,
     current: Token,
     next: Token,
+    /// Doc comments written before `next`
+    next_doc: Vec<String>,
+    /// The token following `next`, which tells whether a contextual keyword starts an
+    /// item
+    after: Token,
+    /// Doc comments written before `after`
+    after_doc: Vec<String>,
     errors: Vec<ParseError>,
 
-    // Items
-    structs: HashMap<String, StructDefinition>,
-    enums: HashMap<String, EnumDefinition>,
-    type_aliases: HashMap<String, TypeAlias>,
+    // Items, in declaration order
+    items: Vec<Item>,
+    imports: Vec<Import>,
 
     // Validation
-    /// Set of user defined types yet to be found
-    pending_types: HashSet<String>,
+    /// Names of the types defined so far
+    types: HashSet<String>,
+    /// Names of the services defined so far
+    services: HashSet<String>,
+    /// Names of the constants defined so far
+    constants: HashSet<String>,
+    /// Set of types brought in scope by imports
+    imported: HashSet<String>,
+    /// Number of type parameters of the imported types. Types of failed imports are not
+    /// listed, their type parameters are not known
+    imported_arities: HashMap<String, usize>,
+    /// Has a whole file (`import "path";`) failed to be imported ? The types it brings in
+    /// scope are then not known, and are not reported when missing
+    failed_import: bool,
+    /// User defined types yet to be found, along with where they were first referenced
+    pending_types: HashMap<String, Span>,
+    /// Default values of fields and values of constants. These are checked once all the
+    /// types are known
+    pending_values: Vec<PendingValue>,
+    /// Uses of user defined types, along with the number of type arguments given. These
+    /// are checked once all the types are known
+    type_uses: Vec<(String, usize, Span)>,
+    /// Type parameters of the generic type being defined
+    type_params: Vec<String>,
+    /// Structs that extend another, along with where the extended type is written. Their
+    /// inherited fields are resolved once all the types are known
+    pending_bases: Vec<(String, Span)>,
 }
 
 mod guards {
@@ -53,8 +221,9 @@ mod guards {
 
     pub fn ty_recursive(parent: &str, ty: &TyKind) -> bool {
         match ty {
-            TyKind::UserDefined(udt) => parent == udt,
+            TyKind::UserDefined(udt) | TyKind::Generic(udt, _) => parent == udt,
             TyKind::Primitive(..)
+            | TyKind::Param(..)
             | TyKind::Array(..)
             | TyKind::Nullable(..)
             | TyKind::Map(..)
@@ -63,71 +232,372 @@ mod guards {
     }
 
     pub fn is_reserved(name: &str) -> bool {
-        match name {
-            "struct" | "string" | "int" | "float" | "bool" => false,
-            _ => true,
+        name != "struct" && PrimitiveType::from_name(name).is_none()
+    }
+
+    /// Words that are only keywords at the start of a top level item, so that they stay
+    /// usable as the names of fields, variants and methods
+    pub const ITEM_KEYWORDS: &[&str] = &["union", "service", "const", "import"];
+
+    /// Does `next`, followed by `after`, start a top level item ?
+    pub fn is_item_start(next: &TokenKind, after: &TokenKind) -> bool {
+        match next {
+            // Unless it names a field, e.g `type: string`
+            TokenKind::Struct | TokenKind::Enum | TokenKind::Type => {
+                !matches!(after, TokenKind::Colon | TokenKind::QuestionMark)
+            }
+            TokenKind::Identifier(word) => match word.as_str() {
+                "import" => matches!(after, TokenKind::StringLiteral(_) | TokenKind::BraceLeft),
+                word => ITEM_KEYWORDS.contains(&word) && matches!(after, TokenKind::Identifier(_)),
+            },
+            _ => false,
         }
     }
 }
 
-impl<L> Parser<L>
+impl<'r, L> Parser<'r, L>
 where
     L: TokenStream,
 {
     pub fn new(mut lexer: L) -> Self {
+        let (next, next_doc) = next_documented(&mut lexer);
+        let (after, after_doc) = next_documented(&mut lexer);
+
         Self {
             current: Token::init(),
-            next: lexer.next_token(),
+            next,
+            next_doc,
+            after,
+            after_doc,
             lexer,
+            resolver: None,
             errors: vec![],
-            structs: HashMap::new(),
-            enums: HashMap::new(),
-            type_aliases: HashMap::new(),
-            pending_types: HashSet::new(),
+            items: vec![],
+            imports: vec![],
+            types: HashSet::new(),
+            services: HashSet::new(),
+            constants: HashSet::new(),
+            imported: HashSet::new(),
+            imported_arities: HashMap::new(),
+            failed_import: false,
+            pending_types: HashMap::new(),
+            pending_values: vec![],
+            type_uses: vec![],
+            type_params: vec![],
+            pending_bases: vec![],
+        }
+    }
+
+    /// Creates a parser that loads imported files through `resolver`
+    pub fn with_resolver(lexer: L, resolver: &'r mut dyn ImportResolver) -> Self {
+        Self {
+            resolver: Some(resolver),
+            ..Self::new(lexer)
         }
     }
 
     fn consume(&mut self) -> &Token {
-        self.current = std::mem::replace(&mut self.next, self.lexer.next_token());
+        let (after, after_doc) = next_documented(&mut self.lexer);
+
+        let next = std::mem::replace(&mut self.after, after);
+        self.current = std::mem::replace(&mut self.next, next);
+        self.next_doc = std::mem::replace(&mut self.after_doc, after_doc);
         &self.current
     }
 
-    fn syntax_error(&mut self, expected: Option<TokenKind>) {
-        self.errors.push(ParseError::UnexpectedToken {
+    /// Is the next token the start of a top level item ?
+    fn at_item_start(&self) -> bool {
+        guards::is_item_start(&self.next.kind, &self.after.kind)
+    }
+
+    fn error(&mut self, kind: ParseErrorKind, span: Span) {
+        self.errors.push(ParseError { kind, span });
+    }
+
+    /// Reports the next (not yet consumed) token as unexpected
+    fn syntax_error(&mut self, expected: Vec<TokenKind>) -> Desync {
+        let kind = ParseErrorKind::UnexpectedToken {
             expected,
-            found: self.current.clone(),
-        });
+            found: self.next.kind.clone(),
+        };
+        self.error(kind, self.next.span());
+
+        Desync
     }
 
     fn custom_error(&mut self, message: &str) {
-        self.errors.push(ParseError::Custom(message.into()));
+        self.error(ParseErrorKind::Custom(message.into()), self.current.span());
+    }
+
+    /// Skips tokens until the next `stop` token (which is consumed as well), a closing `}`
+    /// or the start of a new item, while stepping over nested brackets
+    fn synchronize(&mut self, stop: TokenKind) {
+        let mut depth = 0usize;
+
+        loop {
+            match &self.next.kind {
+                TokenKind::Eof => return,
+                _ if depth == 0 && self.at_item_start() => return,
+                TokenKind::BraceRight if depth == 0 => return,
+                kind if depth == 0 && *kind == stop => {
+                    self.consume();
+                    return;
+                }
+                TokenKind::BraceLeft | TokenKind::ParenLeft | TokenKind::SquareLeft => {
+                    depth += 1;
+                }
+                TokenKind::BraceRight | TokenKind::ParenRight | TokenKind::SquareRight => {
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+
+            self.consume();
+        }
     }
 
-    fn consume_expected(&mut self, expected: TokenKind) {
-        let token = self.consume();
-        if expected != token.kind {
-            self.syntax_error(Some(expected));
+    /// Skips tokens until the start of the next top level item
+    fn synchronize_item(&mut self) {
+        while !(self.at_item_start() || self.next.kind == TokenKind::Eof) {
+            self.consume();
         }
     }
 
-    fn parse_ident(&mut self) -> String {
-        let token = self.consume();
+    fn consume_expected(&mut self, expected: TokenKind) -> PResult<()> {
+        if self.next.kind != expected {
+            return Err(self.syntax_error(vec![expected]));
+        }
 
-        match token.kind {
-            TokenKind::Identifier(ref ident) => ident.clone(),
-            _ => {
-                // TODO: This is ugly
-                self.syntax_error(Some(TokenKind::Identifier("".to_string())));
-                String::new()
+        self.consume();
+        Ok(())
+    }
+
+    fn parse_ident(&mut self) -> PResult<String> {
+        match self.next.kind {
+            TokenKind::Identifier(ref ident) => {
+                let ident = ident.clone();
+                self.consume();
+                Ok(ident)
+            }
+            // TODO: This is ugly
+            _ => Err(self.syntax_error(vec![TokenKind::Identifier("".to_string())])),
+        }
+    }
+
+    /// Parses the name of a field. Unlike the names of types, it can be a keyword, e.g a
+    /// `type` field
+    fn parse_field_name(&mut self) -> PResult<String> {
+        let keyword = match self.next.kind {
+            TokenKind::Struct => "struct",
+            TokenKind::Enum => "enum",
+            TokenKind::Type => "type",
+            _ => return self.parse_ident(),
+        };
+
+        self.consume();
+        Ok(keyword.to_string())
+    }
+
+    fn parse_string(&mut self) -> PResult<String> {
+        match self.next.kind {
+            TokenKind::StringLiteral(ref val) => {
+                let val = val.clone();
+                self.consume();
+                Ok(val)
+            }
+            _ => Err(self.syntax_error(vec![TokenKind::StringLiteral("".to_string())])),
+        }
+    }
+
+    /// Parses `{ item, item, ... }`, calling `item` for every element of the list. A syntax
+    /// error inside an element skips ahead to the next `,` so that the remaining elements
+    /// are still parsed
+    fn parse_braced_list<F>(&mut self, mut item: F) -> PResult<()>
+    where
+        F: FnMut(&mut Self) -> PResult<()>,
+    {
+        self.consume_expected(TokenKind::BraceLeft)?;
+
+        while !(matches!(self.next.kind, TokenKind::BraceRight | TokenKind::Eof)
+            || self.at_item_start())
+        {
+            if item(self).is_err() {
+                self.synchronize(TokenKind::Comma);
+
+                // Reaching the end of the file, the missing `}` is not worth another error
+                if self.next.kind == TokenKind::Eof {
+                    return Err(Desync);
+                }
+                continue;
+            }
+
+            match self.next.kind {
+                TokenKind::Comma => {
+                    self.consume();
+                }
+                TokenKind::BraceRight => break,
+                // Most likely a missing comma, carry on with the next element
+                TokenKind::Identifier(_) => {
+                    self.syntax_error(vec![TokenKind::Comma, TokenKind::BraceRight]);
+                }
+                TokenKind::Eof => {
+                    return Err(self.syntax_error(vec![TokenKind::Comma, TokenKind::BraceRight]));
+                }
+                _ => {
+                    self.syntax_error(vec![TokenKind::Comma, TokenKind::BraceRight]);
+                    self.synchronize(TokenKind::Comma);
+                }
+            }
+        }
+
+        self.consume_expected(TokenKind::BraceRight)
+    }
+
+    /// Parses `@name` or `@name(arg, ...)`
+    fn parse_attribute(&mut self) -> PResult<(Attribute, Span)> {
+        self.consume_expected(TokenKind::At)?;
+        let start = self.current.span();
+        let name = self.parse_ident()?;
+        let mut args = vec![];
+
+        if self.next.kind == TokenKind::ParenLeft {
+            self.consume();
+
+            while self.next.kind != TokenKind::ParenRight {
+                let arg = match self.next.kind.clone() {
+                    TokenKind::StringLiteral(val) => AttributeValue::String(val),
+                    TokenKind::IntLiteral(val) => match i64::try_from(val) {
+                        Ok(val) => AttributeValue::Int(val),
+                        Err(_) => {
+                            let kind = ParseErrorKind::IntOutOfRange(val.to_string());
+                            self.error(kind, self.next.span());
+                            AttributeValue::Int(0)
+                        }
+                    },
+                    _ => {
+                        return Err(self.syntax_error(vec![
+                            TokenKind::IntLiteral(0),
+                            TokenKind::StringLiteral("".to_string()),
+                            TokenKind::ParenRight,
+                        ]))
+                    }
+                };
+
+                self.consume();
+                args.push(arg);
+
+                match self.next.kind {
+                    TokenKind::Comma => {
+                        self.consume();
+                    }
+                    TokenKind::ParenRight => {}
+                    _ => {
+                        return Err(self.syntax_error(vec![TokenKind::Comma, TokenKind::ParenRight]))
+                    }
+                }
+            }
+
+            self.consume();
+        }
+
+        Ok((Attribute { name, args }, start.to(self.current.span())))
+    }
+
+    /// Parses the doc comments and attributes written before an item, field or variant
+    fn parse_prefix(&mut self) -> PResult<Prefix> {
+        let mut prefix = Prefix {
+            doc: std::mem::take(&mut self.next_doc),
+            attributes: vec![],
+        };
+
+        while self.next.kind == TokenKind::At {
+            let attribute = self.parse_attribute()?;
+            prefix.attributes.push(attribute);
+
+            // Doc comments may be written after the attributes as well
+            prefix.doc.append(&mut self.next_doc);
+        }
+
+        Ok(prefix)
+    }
+
+    /// Checks the attributes of `prefix` against the known attributes, now that their
+    /// `target` is known. Invalid attributes are reported and dropped
+    fn finish_prefix(
+        &mut self,
+        prefix: Prefix,
+        target: AttributeTarget,
+    ) -> (Vec<String>, Vec<Attribute>) {
+        let mut attributes: Vec<Attribute> = vec![];
+
+        for (attribute, span) in prefix.attributes {
+            let Some(spec) = attribute_spec(&attribute.name) else {
+                self.error(ParseErrorKind::UnknownAttribute(attribute.name), span);
+                continue;
+            };
+
+            if !spec.applies_to(target) {
+                let kind = ParseErrorKind::MisplacedAttribute {
+                    name: attribute.name,
+                    target,
+                };
+                self.error(kind, span);
+                continue;
+            }
+
+            if attributes.iter().any(|attr| attr.name == attribute.name) {
+                self.error(ParseErrorKind::RepeatedAttribute(attribute.name), span);
+                continue;
+            }
+
+            let args_valid = (spec.required..=spec.params.len()).contains(&attribute.args.len())
+                && attribute.args.iter().zip(spec.params).all(|(arg, param)| {
+                    matches!(
+                        (arg, param),
+                        (AttributeValue::String(..), AttributeParam::String)
+                            | (AttributeValue::Int(..), AttributeParam::Int)
+                    )
+                });
+
+            if !args_valid {
+                let params = spec
+                    .params
+                    .iter()
+                    .enumerate()
+                    .map(|(i, param)| {
+                        let kind = match param {
+                            AttributeParam::String => "string",
+                            AttributeParam::Int => "int",
+                        };
+                        let optional = if i < spec.required { "" } else { "?" };
+                        format!("{}{}", kind, optional)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let expected = format!("@{}({})", spec.name, params);
+                self.error(ParseErrorKind::InvalidAttributeArgs(expected), span);
+                continue;
             }
+
+            if let ("rename", Some(AttributeValue::String(name))) = (spec.name, attribute.value()) {
+                if !is_wire_name(name) {
+                    self.error(ParseErrorKind::InvalidWireName(name.clone()), span);
+                    continue;
+                }
+            }
+
+            attributes.push(attribute);
         }
+
+        (prefix.doc, attributes)
     }
 
     fn is_valid_udt(&self, name: &str) -> bool {
-        self.structs.contains_key(name) || self.enums.contains_key(name)
+        self.imported.contains(name) || self.types.contains(name)
     }
 
-    fn parse_type(&mut self) -> TyKind {
+    fn parse_type(&mut self) -> PResult<TyKind> {
         let ty = if self.next.kind == TokenKind::ParenLeft {
             // Tuple
             self.consume();
@@ -135,17 +605,23 @@ where
             let mut tys: Vec<TyKind> = vec![];
 
             loop {
-                let ty = self.parse_type();
+                let ty = self.parse_type()?;
 
                 // add ty
                 tys.push(ty);
 
-                if matches!(self.next.kind, TokenKind::ParenRight) {
-                    self.consume();
-                    break;
+                match self.next.kind {
+                    TokenKind::ParenRight => {
+                        self.consume();
+                        break;
+                    }
+                    TokenKind::Comma => {
+                        self.consume();
+                    }
+                    _ => {
+                        return Err(self.syntax_error(vec![TokenKind::Comma, TokenKind::ParenRight]))
+                    }
                 }
-
-                self.consume_expected(TokenKind::Comma);
             }
 
             TyKind::Tuple(tys)
@@ -153,219 +629,1383 @@ where
             // Map
             self.consume();
 
-            let ty = self.parse_type();
-            self.consume_expected(TokenKind::AngleRight);
+            let ty = self.parse_type()?;
+            self.consume_expected(TokenKind::AngleRight)?;
 
             TyKind::Map(Box::new(ty))
         } else if self.next.kind == TokenKind::SquareLeft {
             // Array
             self.consume();
 
-            let ty = self.parse_type();
-            self.consume_expected(TokenKind::SquareRight);
+            let ty = self.parse_type()?;
+            self.consume_expected(TokenKind::SquareRight)?;
 
             TyKind::Array(Box::new(ty))
         } else {
-            let name = self.parse_ident();
+            let name = self.parse_ident()?;
+            let name_span = self.current.span();
 
-            match name.as_str() {
-                "string" => TyKind::Primitive(PrimitiveType::String),
-                "int" => TyKind::Primitive(PrimitiveType::Int),
-                "float" => TyKind::Primitive(PrimitiveType::Float),
-                "bool" => TyKind::Primitive(PrimitiveType::Bool),
-                _ => {
-                    // If the referenced type is not yet defined, then add it to
-                    // pending_types to be checked later
-                    if !self.is_valid_udt(&name) {
-                        self.pending_types.insert(name.clone());
-                    }
+            if self.type_params.contains(&name) {
+                TyKind::Param(name)
+            } else {
+                match PrimitiveType::from_name(&name) {
+                    Some(prim) => TyKind::Primitive(prim),
+                    None => {
+                        // If the referenced type is not yet defined, then add it to
+                        // pending_types to be checked later
+                        if !self.is_valid_udt(&name) {
+                            self.pending_types.entry(name.clone()).or_insert(name_span);
+                        }
+
+                        let args = if self.next.kind == TokenKind::AngleLeft {
+                            self.parse_type_args()?
+                        } else {
+                            vec![]
+                        };
 
-                    TyKind::UserDefined(name)
+                        let span = name_span.to(self.current.span());
+                        self.type_uses.push((name.clone(), args.len(), span));
+
+                        if args.is_empty() {
+                            TyKind::UserDefined(name)
+                        } else {
+                            TyKind::Generic(name, args)
+                        }
+                    }
                 }
             }
         };
 
         if self.next.kind == TokenKind::QuestionMark {
             self.consume();
-            TyKind::Nullable(Box::new(ty))
+            Ok(TyKind::Nullable(Box::new(ty)))
         } else {
-            ty
+            Ok(ty)
         }
     }
 
-    fn parse_struct(&mut self) {
-        let struct_name = self.parse_ident();
+    /// Parses the type arguments given to a generic type, e.g `<string, Role>`
+    fn parse_type_args(&mut self) -> PResult<Vec<TyKind>> {
+        self.consume_expected(TokenKind::AngleLeft)?;
 
-        if !guards::is_reserved(&struct_name) {
-            self.errors
-                .push(ParseError::InvalidUdt(struct_name.clone()));
-        } else if self.is_valid_udt(&struct_name) {
-            self.errors
-                .push(ParseError::RedefinedType(struct_name.clone()));
-        }
+        let mut args = vec![];
 
-        let mut struct_ = StructDefinition {
-            name: struct_name,
-            fields: vec![],
-        };
+        loop {
+            args.push(self.parse_type()?);
+
+            match self.next.kind {
+                TokenKind::AngleRight => {
+                    self.consume();
+                    break;
+                }
+                TokenKind::Comma => {
+                    self.consume();
+                }
+                _ => return Err(self.syntax_error(vec![TokenKind::Comma, TokenKind::AngleRight])),
+            }
+        }
 
-        self.consume_expected(TokenKind::BraceLeft);
+        Ok(args)
+    }
 
-        while !(matches!(self.next.kind, TokenKind::BraceRight)) {
-            let field_name = self.parse_ident();
-            self.consume_expected(TokenKind::Colon);
-            let field_type = self.parse_type();
+    /// Parses the type parameters of a generic type, e.g `<T, E>`, if there are any
+    fn parse_type_params(&mut self) -> PResult<Vec<String>> {
+        let mut params: Vec<String> = vec![];
 
-            if guards::ty_recursive(&struct_.name, &field_type) {
-                self.errors
-                    .push(ParseError::RecursiveType(struct_.name.clone()));
-                continue;
-            }
+        if self.next.kind != TokenKind::AngleLeft {
+            return Ok(params);
+        }
 
-            struct_.fields.push(StructField {
-                name: field_name,
-                datatype: field_type,
-            });
+        self.consume();
 
-            if matches!(self.next.kind, TokenKind::Comma) {
-                self.consume();
+        loop {
+            let name = self.parse_ident()?;
+            let name_span = self.current.span();
+
+            if !guards::is_reserved(&name) {
+                self.error(ParseErrorKind::InvalidUdt(name), name_span);
+            } else if params.contains(&name) {
+                let message = format!("Type parameter \"{}\" is already declared", name);
+                self.error(ParseErrorKind::Custom(message), name_span);
             } else {
-                break;
+                params.push(name);
             }
-        }
 
-        self.consume_expected(TokenKind::BraceRight);
+            match self.next.kind {
+                TokenKind::AngleRight => {
+                    self.consume();
+                    break;
+                }
+                TokenKind::Comma => {
+                    self.consume();
+                }
+                _ => return Err(self.syntax_error(vec![TokenKind::Comma, TokenKind::AngleRight])),
+            }
+        }
 
-        self.pending_types.remove(&struct_.name);
-        self.structs.insert(struct_.name.clone(), struct_);
+        Ok(params)
     }
 
-    fn parse_enum(&mut self) {
-        // TODO: a lot of logic is copied from parse_struct(). Remove duplication
-
-        let enum_name = self.parse_ident();
+    /// Parses a literal value, e.g `3`, `-2.5`, `"text"`, `true`, `null`, `Mode.Fast`, `[1, 2]` or
+    /// `{ "key": 1 }`
+    fn parse_value(&mut self) -> PResult<Value> {
+        let value = match self.next.kind.clone() {
+            TokenKind::IntLiteral(val) => Value::Int(val),
+            TokenKind::FloatLiteral(text) => match text.parse::<f64>() {
+                Ok(val) if val.is_finite() => Value::Float(val),
+                _ => {
+                    self.consume();
+                    let message = format!("Float `{}` is out of range", text);
+                    self.error(ParseErrorKind::Custom(message), self.current.span());
+                    return Ok(Value::Float(0.0));
+                }
+            },
+            TokenKind::StringLiteral(val) => Value::String(val),
+            TokenKind::Identifier(ident) => match ident.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => {
+                    self.consume();
+                    self.consume_expected(TokenKind::Dot)?;
+                    let variant = self.parse_ident()?;
 
-        if !guards::is_reserved(&enum_name) {
-            self.errors.push(ParseError::InvalidUdt(enum_name.clone()));
-        } else if self.is_valid_udt(&enum_name) {
-            self.errors
-                .push(ParseError::RedefinedType(enum_name.clone()));
-        }
+                    return Ok(Value::Variant {
+                        enum_name: ident,
+                        variant,
+                    });
+                }
+            },
+            TokenKind::SquareLeft => {
+                let mut values = vec![];
+                self.parse_value_list(TokenKind::SquareRight, |this| {
+                    values.push(this.parse_value()?);
+                    Ok(())
+                })?;
+
+                return Ok(Value::Array(values));
+            }
+            TokenKind::ParenLeft => {
+                let mut values = vec![];
+                self.parse_value_list(TokenKind::ParenRight, |this| {
+                    values.push(this.parse_value()?);
+                    Ok(())
+                })?;
+
+                return Ok(Value::Tuple(values));
+            }
+            TokenKind::BraceLeft => {
+                let mut entries = vec![];
+                self.parse_value_list(TokenKind::BraceRight, |this| {
+                    let key = this.parse_string()?;
+                    if entries.iter().any(|(other, _)| *other == key) {
+                        let kind = ParseErrorKind::RepeatedKey(key.clone());
+                        this.error(kind, this.current.span());
+                    }
+                    this.consume_expected(TokenKind::Colon)?;
+                    entries.push((key, this.parse_value()?));
+                    Ok(())
+                })?;
 
-        let mut enum_ = EnumDefinition {
-            name: enum_name,
-            variants: vec![],
+                return Ok(Value::Map(entries));
+            }
+            _ => {
+                return Err(self.syntax_error(vec![
+                    TokenKind::IntLiteral(0),
+                    TokenKind::FloatLiteral("".to_string()),
+                    TokenKind::StringLiteral("".to_string()),
+                    TokenKind::Identifier("".to_string()),
+                    TokenKind::SquareLeft,
+                    TokenKind::BraceLeft,
+                    TokenKind::ParenLeft,
+                ]))
+            }
         };
 
-        self.consume_expected(TokenKind::BraceLeft);
+        self.consume();
+        Ok(value)
+    }
 
-        let mut curr_int_value = 0;
-        let mut is_int_enum = true;
-        let mut type_decided = false;
+    /// Parses the comma separated elements of an array, tuple or map value, up to and including
+    /// `close`. After a syntax error the rest of the value is skipped, so that the caller
+    /// carries on right after it
+    fn parse_value_list<F>(&mut self, close: TokenKind, mut item: F) -> PResult<()>
+    where
+        F: FnMut(&mut Self) -> PResult<()>,
+    {
+        // The opening bracket
+        self.consume();
+
+        let result = loop {
+            if self.next.kind == close {
+                break Ok(());
+            }
 
-        while !(matches!(self.next.kind, TokenKind::BraceRight)) {
-            let variant_name = self.parse_ident();
-            let mut variant_value: EnumVariantValue = EnumVariantValue::Int(0);
+            if let Err(desync) = item(self) {
+                break Err(desync);
+            }
 
-            if matches!(self.next.kind, TokenKind::Equal) {
-                self.consume();
+            match &self.next.kind {
+                TokenKind::Comma => {
+                    self.consume();
+                }
+                kind if *kind == close => {}
+                _ => break Err(self.syntax_error(vec![TokenKind::Comma, close.clone()])),
+            }
+        };
 
-                match self.next.kind.clone() {
-                    TokenKind::StringLiteral(val) => {
-                        self.consume();
-                        if type_decided && is_int_enum {
-                            self.custom_error("Expected an int, found string");
-                        } else {
-                            variant_value = EnumVariantValue::String(val.clone());
-                            is_int_enum = false;
-                        }
-                    }
-                    TokenKind::IntLiteral(val) => {
-                        self.consume();
+        if result.is_err() {
+            self.skip_value(&close);
+        }
 
-                        if type_decided && !is_int_enum {
-                            self.custom_error("Expected a string, found int");
-                        } else {
-                            variant_value = EnumVariantValue::Int(val);
-                            curr_int_value = val + 1;
-                        }
-                    }
-                    _ => {
-                        self.syntax_error(None);
-                    }
+        self.consume_expected(close)?;
+        result
+    }
+
+    /// Skips tokens up to the `close` bracket ending the current value, while stepping
+    /// over nested brackets
+    fn skip_value(&mut self, close: &TokenKind) {
+        let mut depth = 0usize;
+
+        loop {
+            match &self.next.kind {
+                TokenKind::Eof => return,
+                _ if self.at_item_start() => return,
+                kind if depth == 0 && kind == close => return,
+                // A mismatched bracket, most likely closing what encloses the value
+                TokenKind::BraceRight | TokenKind::SquareRight | TokenKind::ParenRight
+                    if depth == 0 =>
+                {
+                    return
                 }
-            } else {
-                if !type_decided || is_int_enum {
-                    variant_value = EnumVariantValue::Int(curr_int_value);
-                    curr_int_value += 1;
-                } else {
-                    self.custom_error("Expected a string");
+                TokenKind::BraceLeft | TokenKind::SquareLeft | TokenKind::ParenLeft => depth += 1,
+                TokenKind::BraceRight | TokenKind::SquareRight | TokenKind::ParenRight => {
+                    depth = depth.saturating_sub(1);
                 }
+                _ => {}
             }
 
-            type_decided = true;
+            self.consume();
+        }
+    }
 
-            enum_.variants.push(EnumVariant {
-                name: variant_name,
-                value: variant_value,
-            });
+    /// Parses a braced, comma separated list of fields. `owner` is the name of the
+    /// user-defined type the fields belong to
+    fn parse_fields(&mut self, owner: &str) -> PResult<Vec<StructField>> {
+        let mut fields = vec![];
+
+        let result = self.parse_braced_list(|this| {
+            let prefix = this.parse_prefix()?;
+            let (doc, attributes) = this.finish_prefix(prefix, AttributeTarget::Field);
+            let field_name = this.parse_field_name()?;
+            let name_span = this.current.span();
+
+            // Python and Rust both use snake_case names, `fooBar` and `foo_bar` would clash
+            let snake = snake_case(&field_name);
+            let colliding = fields
+                .iter()
+                .find(|field: &&StructField| snake_case(&field.name) == snake);
+
+            if let Some(other) = colliding {
+                let kind = match other.name == field_name {
+                    true => ParseErrorKind::RedefinedField {
+                        name: field_name.clone(),
+                        owner: owner.to_string(),
+                    },
+                    false => ParseErrorKind::CollidingField {
+                        name: field_name.clone(),
+                        other: other.name.clone(),
+                    },
+                };
+                this.error(kind, name_span);
+            }
 
-            if matches!(self.next.kind, TokenKind::Comma) {
-                self.consume();
-            } else {
-                break;
+            let optional = this.next.kind == TokenKind::QuestionMark;
+            if optional {
+                this.consume();
             }
-        }
 
-        self.consume_expected(TokenKind::BraceRight);
+            this.consume_expected(TokenKind::Colon)?;
+            let type_start = this.next.span();
+            let field_type = this.parse_type()?;
+            let type_span = type_start.to(this.current.span());
 
-        self.pending_types.remove(&enum_.name);
-        self.enums.insert(enum_.name.clone(), enum_);
-    }
+            let default = if this.next.kind == TokenKind::Equal {
+                this.consume();
+                let value_start = this.next.span();
+                let value = this.parse_value()?;
+                let value_span = value_start.to(this.current.span());
 
-    fn parse_type_alias(&mut self) {
-        let alias_name = self.parse_ident();
-        self.consume_expected(TokenKind::Equal);
-        let ty = self.parse_type();
-        self.consume_expected(TokenKind::Semicolon);
+                if optional {
+                    let message = "Optional fields can not have a default value".to_string();
+                    this.error(ParseErrorKind::Custom(message), value_span);
+                }
 
-        self.pending_types.remove(&alias_name);
+                this.pending_values.push((
+                    field_type.clone(),
+                    value.clone(),
+                    value_span,
+                    ParseErrorKind::InvalidDefault,
+                ));
+
+                Some(DefaultValue {
+                    value,
+                    ty: field_type.clone(),
+                })
+            } else {
+                None
+            };
+
+            if guards::ty_recursive(owner, &field_type) {
+                this.error(ParseErrorKind::RecursiveType(owner.to_string()), type_span);
+            } else {
+                let field = StructField {
+                    name: field_name,
+                    datatype: field_type,
+                    doc,
+                    attributes,
+                    optional,
+                    default,
+                };
+
+                // Fields sharing a name were reported above, only `@rename`s can clash here
+                let clashing = fields.iter().find(|other| {
+                    other.name != field.name && other.wire_name() == field.wire_name()
+                });
+
+                if let Some(other) = clashing {
+                    let kind = ParseErrorKind::RepeatedWireName {
+                        name: field.name.clone(),
+                        wire: field.wire_name().to_string(),
+                        other: other.name.clone(),
+                    };
+                    this.error(kind, name_span);
+                }
+
+                fields.push(field);
+            }
+
+            Ok(())
+        });
+
+        result.map(|_| fields)
+    }
+
+    /// Parses the name of a new user-defined type and reports if it is invalid or taken
+    fn parse_udt_name(&mut self) -> PResult<String> {
+        let name = self.parse_ident()?;
+        let name_span = self.current.span();
+
+        if !guards::is_reserved(&name) {
+            self.error(ParseErrorKind::InvalidUdt(name.clone()), name_span);
+        } else if self.is_valid_udt(&name) {
+            self.error(ParseErrorKind::RedefinedType(name.clone()), name_span);
+        }
+
+        Ok(name)
+    }
+
+    fn add_item(&mut self, item: Item) {
+        let name = item.name().to_string();
+
+        match item {
+            Item::Service(..) => {
+                self.services.insert(name);
+            }
+            Item::Constant(..) => {
+                self.constants.insert(name);
+            }
+            _ => {
+                self.pending_types.remove(&name);
+                self.types.insert(name);
+            }
+        }
+
+        self.items.push(item);
+    }
+
+    fn parse_struct(&mut self, prefix: Prefix) -> PResult<()> {
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Struct);
+        let struct_name = self.parse_udt_name()?;
+        self.type_params = self.parse_type_params()?;
+
+        let base = match self.parse_base(&struct_name) {
+            Ok(base) => base,
+            Err(desync) => {
+                self.type_params.clear();
+                return Err(desync);
+            }
+        };
+
+        // Register the struct even if its body is malformed, so that its uses are not
+        // reported as unknown types
+        let (fields, result) = match self.parse_fields(&struct_name) {
+            Ok(fields) => (fields, Ok(())),
+            Err(desync) => (vec![], Err(desync)),
+        };
+
+        let struct_ = StructDefinition {
+            name: struct_name,
+            params: std::mem::take(&mut self.type_params),
+            base,
+            fields,
+            inherited: vec![],
+            doc,
+            attributes,
+        };
+
+        self.add_item(Item::Struct(struct_));
+
+        result
+    }
+
+    /// Parses the `extends Base` clause of a struct, if there is one
+    fn parse_base(&mut self, struct_name: &str) -> PResult<Option<TyKind>> {
+        // "extends" is only a keyword in this position
+        if !matches!(self.next.kind, TokenKind::Identifier(ref kw) if kw == "extends") {
+            return Ok(None);
+        }
+        self.consume();
+
+        let base_start = self.next.span();
+        let base = self.parse_type()?;
+        let base_span = base_start.to(self.current.span());
+
+        match base {
+            TyKind::UserDefined(..) | TyKind::Generic(..) => {
+                self.pending_bases
+                    .push((struct_name.to_string(), base_span));
+                Ok(Some(base))
+            }
+            _ => {
+                let message = "Only structs can be extended".to_string();
+                self.error(ParseErrorKind::Custom(message), base_span);
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_enum(&mut self, prefix: Prefix) -> PResult<()> {
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Enum);
+        let enum_name = self.parse_udt_name()?;
+
+        let mut variants = vec![];
+
+        let mut curr_int_value = 0;
+        let mut is_int_enum = true;
+        let mut type_decided = false;
+
+        let result = self.parse_braced_list(|this| {
+            let prefix = this.parse_prefix()?;
+            let (variant_doc, variant_attributes) =
+                this.finish_prefix(prefix, AttributeTarget::Variant);
+            let variant_name = this.parse_ident()?;
+            let mut variant_value: EnumVariantValue = EnumVariantValue::Int(0);
+
+            if matches!(this.next.kind, TokenKind::Equal) {
+                this.consume();
+
+                match this.next.kind.clone() {
+                    TokenKind::StringLiteral(val) => {
+                        this.consume();
+                        if type_decided && is_int_enum {
+                            this.custom_error("Expected an int, found string");
+                        } else {
+                            variant_value = EnumVariantValue::String(val.clone());
+                            is_int_enum = false;
+                        }
+                    }
+                    TokenKind::IntLiteral(val) => {
+                        this.consume();
+
+                        if type_decided && !is_int_enum {
+                            this.custom_error("Expected a string, found int");
+                        } else if let Ok(val) = i32::try_from(val) {
+                            variant_value = EnumVariantValue::Int(val);
+                            curr_int_value = val.wrapping_add(1);
+                        } else {
+                            this.custom_error("Enum values must fit in 32 bits");
+                        }
+                    }
+                    _ => {
+                        return Err(this.syntax_error(vec![
+                            TokenKind::IntLiteral(0),
+                            TokenKind::StringLiteral("".to_string()),
+                        ]));
+                    }
+                }
+            } else if !type_decided || is_int_enum {
+                variant_value = EnumVariantValue::Int(curr_int_value);
+                curr_int_value += 1;
+            } else {
+                this.custom_error("Expected a string");
+            }
+
+            type_decided = true;
+
+            variants.push(EnumVariant {
+                name: variant_name,
+                value: variant_value,
+                doc: variant_doc,
+                attributes: variant_attributes,
+            });
+
+            Ok(())
+        });
+
+        let enum_ = EnumDefinition {
+            name: enum_name,
+            variants,
+            doc,
+            attributes,
+        };
+
+        self.add_item(Item::Enum(enum_));
+
+        result
+    }
+
+    fn parse_type_alias(&mut self, prefix: Prefix) -> PResult<()> {
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Alias);
+        let alias_name = self.parse_udt_name()?;
+        let params = self.parse_type_params()?;
+        self.consume_expected(TokenKind::Equal)?;
+
+        self.type_params = params;
+        let type_start = self.next.span();
+        let ty = self.parse_type();
+        let params = std::mem::take(&mut self.type_params);
+        let ty = ty?;
+
+        // Only the alias closing a cycle sees all of it, so every cycle is reported once
+        if self.is_alias_cycle(&alias_name, &ty) {
+            let kind = ParseErrorKind::RecursiveAlias(alias_name.clone());
+            self.error(kind, type_start.to(self.current.span()));
+        }
+
+        self.consume_expected(TokenKind::Semicolon)?;
 
         let type_alias = TypeAlias {
             name: alias_name,
+            params,
             typ: ty,
+            doc,
+            attributes,
+        };
+
+        self.add_item(Item::TypeAlias(type_alias));
+
+        Ok(())
+    }
+
+    /// Does `ty`, the type of the alias `name`, lead back to `name` through the aliases
+    /// defined so far ?
+    fn is_alias_cycle(&self, name: &str, ty: &TyKind) -> bool {
+        let aliases = self
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::TypeAlias(alias) => Some((alias.name.as_str(), &alias.typ)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut visited = HashSet::new();
+        let mut pending = vec![ty];
+
+        while let Some(ty) = pending.pop() {
+            let mut udts = vec![];
+            ty.collect_udts(&mut udts);
+
+            for udt in udts {
+                if udt == name {
+                    return true;
+                }
+
+                if visited.insert(udt) {
+                    pending.extend(aliases.get(udt));
+                }
+            }
+        }
+
+        false
+    }
+
+    fn parse_union(&mut self, prefix: Prefix) -> PResult<()> {
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Union);
+        let union_name = self.parse_udt_name()?;
+        let name_span = self.current.span();
+
+        let mut variants: Vec<UnionVariant> = vec![];
+
+        let result = self.parse_braced_list(|this| {
+            let prefix = this.parse_prefix()?;
+            let (variant_doc, variant_attributes) =
+                this.finish_prefix(prefix, AttributeTarget::Variant);
+            let variant_name = this.parse_ident()?;
+            let variant_span = this.current.span();
+
+            if variants.iter().any(|variant| variant.name == variant_name) {
+                let message = format!(
+                    "Variant \"{}\" is already defined in union \"{}\"",
+                    variant_name, union_name
+                );
+                this.error(ParseErrorKind::Custom(message), variant_span);
+            }
+
+            // Variants without a body carry no data other than the tag
+            let fields = if matches!(this.next.kind, TokenKind::BraceLeft) {
+                this.parse_fields(&union_name)?
+            } else {
+                vec![]
+            };
+
+            if let Some(field) = fields.iter().find(|field| field.wire_name() == UNION_TAG) {
+                let message = format!(
+                    "Field \"{}\" of variant \"{}\" clashes with the union tag",
+                    field.name, variant_name
+                );
+                this.error(ParseErrorKind::Custom(message), variant_span);
+            }
+
+            variants.push(UnionVariant {
+                name: variant_name,
+                fields,
+                doc: variant_doc,
+                attributes: variant_attributes,
+            });
+
+            Ok(())
+        });
+
+        if result.is_ok() && variants.is_empty() {
+            let message = format!("Union \"{}\" has no variants", union_name);
+            self.error(ParseErrorKind::Custom(message), name_span);
+        }
+
+        let union_ = UnionDefinition {
+            name: union_name,
+            variants,
+            doc,
+            attributes,
+        };
+
+        self.add_item(Item::Union(union_));
+
+        result
+    }
+
+    fn parse_method(&mut self, service: &ServiceDefinition) -> PResult<ServiceMethod> {
+        // method(Input) -> Output;
+        let prefix = self.parse_prefix()?;
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Method);
+        let method_name = self.parse_ident()?;
+        let method_span = self.current.span();
+
+        self.consume_expected(TokenKind::ParenLeft)?;
+        let input = self.parse_type()?;
+        self.consume_expected(TokenKind::ParenRight)?;
+
+        self.consume_expected(TokenKind::Arrow)?;
+        let output = self.parse_type()?;
+        self.consume_expected(TokenKind::Semicolon)?;
+
+        if service.methods.iter().any(|m| m.name == method_name) {
+            let kind = ParseErrorKind::RedefinedMethod {
+                service: service.name.clone(),
+                method: method_name.clone(),
+            };
+            self.error(kind, method_span);
+        }
+
+        Ok(ServiceMethod {
+            name: method_name,
+            input,
+            output,
+            doc,
+            attributes,
+        })
+    }
+
+    fn parse_service(&mut self, prefix: Prefix) -> PResult<()> {
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Service);
+        let service_name = self.parse_ident()?;
+
+        if self.services.contains(&service_name) {
+            let kind = ParseErrorKind::RedefinedService(service_name.clone());
+            self.error(kind, self.current.span());
+        }
+
+        let mut service = ServiceDefinition {
+            name: service_name,
+            methods: vec![],
+            doc,
+            attributes,
+        };
+
+        self.consume_expected(TokenKind::BraceLeft)?;
+
+        while !(matches!(self.next.kind, TokenKind::BraceRight | TokenKind::Eof)
+            || self.at_item_start())
+        {
+            match self.parse_method(&service) {
+                Ok(method) => service.methods.push(method),
+                Err(Desync) => self.synchronize(TokenKind::Semicolon),
+            }
+        }
+
+        let result = self.consume_expected(TokenKind::BraceRight);
+
+        self.add_item(Item::Service(service));
+
+        result
+    }
+
+    fn parse_constant(&mut self, prefix: Prefix) -> PResult<()> {
+        // const NAME: type = value;
+
+        let (doc, attributes) = self.finish_prefix(prefix, AttributeTarget::Constant);
+        let name = self.parse_ident()?;
+
+        if self.constants.contains(&name) {
+            let kind = ParseErrorKind::RedefinedConstant(name.clone());
+            self.error(kind, self.current.span());
+        }
+
+        self.consume_expected(TokenKind::Colon)?;
+        let typ = self.parse_type()?;
+        self.consume_expected(TokenKind::Equal)?;
+
+        let value_start = self.next.span();
+        let value = self.parse_value()?;
+        let value_span = value_start.to(self.current.span());
+
+        self.consume_expected(TokenKind::Semicolon)?;
+
+        self.pending_values.push((
+            typ.clone(),
+            value.clone(),
+            value_span,
+            ParseErrorKind::InvalidConstant,
+        ));
+
+        self.add_item(Item::Constant(ConstantDefinition {
+            name,
+            resolved: typ.clone(),
+            typ,
+            value,
+            doc,
+            attributes,
+        }));
+
+        Ok(())
+    }
+
+    fn parse_import(&mut self) -> PResult<()> {
+        // import "path";
+        // import { A, B } from "path";
+
+        let mut selected: Option<Vec<(String, Span)>> = None;
+
+        if matches!(self.next.kind, TokenKind::BraceLeft) {
+            let mut names = vec![];
+
+            self.parse_braced_list(|this| {
+                let name = this.parse_ident()?;
+                names.push((name, this.current.span()));
+                Ok(())
+            })?;
+
+            // "from" is only a keyword in this position
+            if !matches!(self.next.kind, TokenKind::Identifier(ref kw) if kw == "from") {
+                return Err(self.syntax_error(vec![TokenKind::Identifier("from".to_string())]));
+            }
+            self.consume();
+
+            selected = Some(names);
+        }
+
+        let path = self.parse_string()?;
+        let path_span = self.current.span();
+        self.consume_expected(TokenKind::Semicolon)?;
+
+        let exported = match self.resolver.as_mut() {
+            Some(resolver) => resolver.resolve(&path),
+            None => Err(ParseErrorKind::ImportFailed {
+                path: path.clone(),
+                reason: "imports are not supported here".to_string(),
+            }),
+        };
+
+        let exported = match exported {
+            Ok(exported) => exported,
+            Err(kind) => {
+                self.error(kind, path_span);
+
+                // Uses of the missing types would only repeat this error
+                match selected {
+                    Some(names) => {
+                        for (name, _) in names {
+                            self.pending_types.remove(&name);
+                            self.imported.insert(name);
+                        }
+                    }
+                    None => self.failed_import = true,
+                }
+                return Ok(());
+            }
         };
 
-        self.type_aliases
-            .insert(type_alias.name.clone(), type_alias);
+        let names = match selected {
+            Some(names) => {
+                let mut found = vec![];
+
+                for (name, span) in names {
+                    if let Some(export) = exported.iter().find(|(n, _)| *n == name) {
+                        found.push(export.clone());
+                    } else {
+                        let kind = ParseErrorKind::UnknownImport {
+                            name,
+                            path: path.clone(),
+                        };
+                        self.error(kind, span);
+                    }
+                }
+
+                found
+            }
+            None => exported,
+        };
+
+        for (name, arity) in names.iter() {
+            if self.is_valid_udt(name) {
+                self.error(ParseErrorKind::RedefinedType(name.clone()), path_span);
+            }
+
+            self.imported.insert(name.clone());
+            self.imported_arities.insert(name.clone(), *arity);
+            self.pending_types.remove(name);
+        }
+
+        let names = names.into_iter().map(|(name, _)| name).collect();
+        self.imports.push(Import::new(path, names));
+
+        Ok(())
     }
 
     pub fn parse(mut self) -> Result<Program, Vec<ParseError>> {
         loop {
-            self.consume();
-            match self.current.kind {
-                TokenKind::Struct => self.parse_struct(),
-                TokenKind::Enum => self.parse_enum(),
-                TokenKind::Type => self.parse_type_alias(),
-                TokenKind::Eof => break,
-                _ => self.syntax_error(None),
+            let prefix = match self.parse_prefix() {
+                Ok(prefix) => prefix,
+                Err(Desync) => {
+                    self.synchronize_item();
+                    continue;
+                }
+            };
+
+            // Nothing but items is expected here, so contextual keywords start an item
+            // whatever follows them
+            let keyword = match &self.next.kind {
+                TokenKind::Eof if prefix.attributes.is_empty() => break,
+                TokenKind::Struct => Some("struct"),
+                TokenKind::Enum => Some("enum"),
+                TokenKind::Type => Some("type"),
+                TokenKind::Identifier(word) => guards::ITEM_KEYWORDS
+                    .iter()
+                    .copied()
+                    .find(|keyword| *keyword == word.as_str()),
+                _ => None,
+            };
+
+            let result = match keyword {
+                Some(keyword) => {
+                    self.consume();
+
+                    match keyword {
+                        "struct" => self.parse_struct(prefix),
+                        "enum" => self.parse_enum(prefix),
+                        "type" => self.parse_type_alias(prefix),
+                        "union" => self.parse_union(prefix),
+                        "service" => self.parse_service(prefix),
+                        "const" => self.parse_constant(prefix),
+                        _ => {
+                            if let Some((_, span)) = prefix.attributes.first() {
+                                let message = "Imports can not have attributes".to_string();
+                                self.error(ParseErrorKind::Custom(message), *span);
+                            }
+                            self.parse_import()
+                        }
+                    }
+                }
+                None => Err(self.syntax_error(vec![])),
+            };
+
+            if result.is_err() {
+                self.synchronize_item();
+            }
+        }
+
+        let mut arities = std::mem::take(&mut self.imported_arities);
+        for item in self.items.iter().filter(|item| item.is_type()) {
+            arities.insert(item.name().to_string(), item.type_params().len());
+        }
+
+        for (name, found, span) in std::mem::take(&mut self.type_uses) {
+            match arities.get(&name) {
+                Some(&expected) if expected != found => {
+                    self.error(
+                        ParseErrorKind::TypeArgCount {
+                            name,
+                            expected,
+                            found,
+                        },
+                        span,
+                    );
+                }
+                _ => {}
             }
         }
 
-        self.errors.extend(
-            self.pending_types
-                .into_iter()
-                .map(|ty| ParseError::UnknownType(ty)),
-        );
+        let mut inheritance = Inheritance::new(&self.items);
+        let mut base_errors = vec![];
+
+        for (name, span) in std::mem::take(&mut self.pending_bases) {
+            let Some(&struct_) = inheritance.structs.get(name.as_str()) else {
+                continue;
+            };
+
+            let base_name = match &struct_.base {
+                Some(TyKind::UserDefined(base) | TyKind::Generic(base, _)) => base,
+                _ => continue,
+            };
+
+            // Unknown types are already reported
+            if self.pending_types.contains_key(base_name) {
+                continue;
+            }
+
+            let message = if self.imported.contains(base_name) {
+                format!("Imported struct \"{}\" can not be extended", base_name)
+            } else if !inheritance.structs.contains_key(base_name.as_str()) {
+                format!(
+                    "Type \"{}\" is not a struct and can not be extended",
+                    base_name
+                )
+            } else if let Some(inherited) = inheritance.inherited(&name) {
+                for field in struct_.fields.iter() {
+                    let collides = inherited.iter().any(|other| {
+                        snake_case(&other.name) == snake_case(&field.name)
+                            || other.wire_name() == field.wire_name()
+                    });
+
+                    if collides {
+                        let message = format!(
+                            "Field \"{}\" of \"{}\" is already defined in \"{}\"",
+                            field.name, name, base_name
+                        );
+                        base_errors.push((message, span));
+                    }
+                }
+                continue;
+            } else {
+                format!("Struct \"{}\" extends itself", name)
+            };
 
-        if self.errors.len() > 0 {
+            base_errors.push((message, span));
+        }
+
+        let inherited = inheritance.into_resolved();
+
+        for (message, span) in base_errors {
+            self.error(ParseErrorKind::Custom(message), span);
+        }
+
+        let lookup = TypeLookup::new(&self.items, &self.imported);
+
+        for (ty, value, span, invalid) in std::mem::take(&mut self.pending_values) {
+            // Values of unknown types are already reported
+            let mut udts = vec![];
+            ty.collect_udts(&mut udts);
+            if udts.iter().any(|udt| self.pending_types.contains_key(*udt)) {
+                continue;
+            }
+
+            if lookup.check(&ty, &value).is_none() {
+                self.error(invalid(ty.to_string()), span);
+            }
+        }
+
+        if self.failed_import {
+            self.pending_types.clear();
+        }
+
+        let mut unknown_types = self.pending_types.into_iter().collect::<Vec<_>>();
+        unknown_types.sort_by_key(|(_, span)| span.start);
+
+        self.errors
+            .extend(unknown_types.into_iter().map(|(ty, span)| ParseError {
+                kind: ParseErrorKind::UnknownType(ty),
+                span,
+            }));
+
+        if !self.errors.is_empty() {
             Err(self.errors)
         } else {
+            let mut items = self.items;
+            lookup.resolve_defaults(&mut items);
+
+            for item in items.iter_mut() {
+                if let Item::Struct(struct_) = item {
+                    if let Some(fields) = inherited.get(&struct_.name) {
+                        struct_.inherited = fields
+                            .iter()
+                            .map(|field| lookup.resolve_default(field.clone()))
+                            .collect();
+                    }
+                }
+            }
+
             Ok(Program {
-                structs: self.structs.into_values().collect(),
-                enums: self.enums.into_values().collect(),
-                type_aliases: self.type_aliases.into_values().collect(),
+                imports: self.imports,
+                items: dependency_order(items),
+            })
+        }
+    }
+}
+
+/// Returns the next token of `lexer` that is not a doc comment, along with the lines of
+/// the doc comments before it
+fn next_documented(lexer: &mut impl TokenStream) -> (Token, Vec<String>) {
+    let mut doc = vec![];
+
+    loop {
+        let token = lexer.next_token();
+
+        match token.kind {
+            TokenKind::DocComment(text) => doc.push(text),
+            _ => return (token, doc),
+        }
+    }
+}
+
+/// What is known about the types in scope when checking default values
+struct TypeLookup {
+    /// Type aliases, along with their type parameters
+    aliases: HashMap<String, (Vec<String>, TyKind)>,
+    /// Names of the variants of every enum
+    enums: HashMap<String, Vec<String>>,
+    imported: HashSet<String>,
+}
+
+impl TypeLookup {
+    fn new(items: &[Item], imported: &HashSet<String>) -> Self {
+        let mut aliases = HashMap::new();
+        let mut enums = HashMap::new();
+
+        for item in items.iter() {
+            match item {
+                Item::TypeAlias(alias) => {
+                    aliases.insert(
+                        alias.name.clone(),
+                        (alias.params.clone(), alias.typ.clone()),
+                    );
+                }
+                Item::Enum(enum_) => {
+                    let variants = enum_.variants.iter().map(|v| v.name.clone()).collect();
+                    enums.insert(enum_.name.clone(), variants);
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            aliases,
+            enums,
+            imported: imported.clone(),
+        }
+    }
+
+    /// Replaces type aliases at the top of `ty` by the types they stand for
+    fn unalias(&self, ty: &TyKind) -> TyKind {
+        let mut ty = ty.clone();
+
+        // Bounded, in case of aliases referring to each other
+        for _ in 0..=self.aliases.len() {
+            match &ty {
+                TyKind::UserDefined(name) if self.aliases.contains_key(name) => {
+                    ty = self.aliases[name].1.clone();
+                }
+                TyKind::Generic(name, args) => match self.aliases.get(name) {
+                    Some((params, typ)) if params.len() == args.len() => {
+                        ty = typ.substitute(params, args);
+                    }
+                    _ => break,
+                },
+                _ => break,
+            }
+        }
+
+        ty
+    }
+
+    /// Checks that `value` is a valid value of type `ty`. If it is, returns `ty` with the
+    /// type aliases along the way replaced by the types they stand for
+    fn check(&self, ty: &TyKind, value: &Value) -> Option<TyKind> {
+        let ty = self.unalias(ty);
+
+        let valid = match (&ty, value) {
+            (TyKind::Nullable(..), Value::Null) => true,
+            (TyKind::Nullable(inner), value) => {
+                let inner = self.check(inner, value)?;
+                return Some(TyKind::Nullable(Box::new(inner)));
+            }
+            (TyKind::Primitive(prim), Value::Int(val)) => {
+                let range = match prim {
+                    PrimitiveType::Int => i32::MIN as i128..=i32::MAX as i128,
+                    PrimitiveType::I8 => i8::MIN as i128..=i8::MAX as i128,
+                    PrimitiveType::I16 => i16::MIN as i128..=i16::MAX as i128,
+                    PrimitiveType::U8 => 0..=u8::MAX as i128,
+                    PrimitiveType::U16 => 0..=u16::MAX as i128,
+                    PrimitiveType::U32 => 0..=u32::MAX as i128,
+                    PrimitiveType::I64 | PrimitiveType::Float | PrimitiveType::F32 => {
+                        i64::MIN as i128..=i64::MAX as i128
+                    }
+                    // A duration is a number of seconds, which can not be negative
+                    PrimitiveType::Duration => 0..=i64::MAX as i128,
+                    PrimitiveType::U64 => 0..=u64::MAX as i128,
+                    _ => return None,
+                };
+                range.contains(val)
+            }
+            (TyKind::Primitive(prim), Value::Float(val)) => match prim {
+                PrimitiveType::Float => true,
+                PrimitiveType::Duration => *val >= 0.0,
+                // Rounded to the nearest f32, e.g `3.4028235e38` is `f32::MAX`
+                PrimitiveType::F32 => (*val as f32).is_finite(),
+                _ => false,
+            },
+            (TyKind::Primitive(prim), Value::String(..)) => {
+                matches!(prim, PrimitiveType::String | PrimitiveType::Url)
+            }
+            (TyKind::Primitive(prim), Value::Bool(..)) => matches!(prim, PrimitiveType::Bool),
+            (TyKind::Array(inner), Value::Array(values)) => {
+                let mut resolved = (**inner).clone();
+                for value in values.iter() {
+                    let checked = self.check(inner, value)?;
+                    // `null` says nothing about the non-null type
+                    if *value != Value::Null {
+                        resolved = checked;
+                    }
+                }
+                return Some(TyKind::Array(Box::new(resolved)));
+            }
+            (TyKind::Map(inner), Value::Map(entries)) => {
+                let mut resolved = (**inner).clone();
+                for (_, value) in entries.iter() {
+                    let checked = self.check(inner, value)?;
+                    // `null` says nothing about the non-null type
+                    if *value != Value::Null {
+                        resolved = checked;
+                    }
+                }
+                return Some(TyKind::Map(Box::new(resolved)));
+            }
+            (TyKind::Tuple(tys), Value::Tuple(values)) if tys.len() == values.len() => {
+                let resolved = tys
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(ty, value)| self.check(ty, value))
+                    .collect::<Option<_>>()?;
+                return Some(TyKind::Tuple(resolved));
+            }
+            (TyKind::UserDefined(name), Value::Variant { enum_name, variant }) => {
+                name == enum_name
+                    && match self.enums.get(name) {
+                        Some(variants) => variants.contains(variant),
+                        // The variants of imported enums are not known here
+                        None => self.imported.contains(name),
+                    }
+            }
+            _ => false,
+        };
+
+        valid.then_some(ty)
+    }
+
+    /// Replaces the type of the default value of `field` with the resolved type
+    fn resolve_default(&self, mut field: StructField) -> StructField {
+        if let Some(default) = field.default.as_mut() {
+            if let Some(ty) = self.check(&field.datatype, &default.value) {
+                default.ty = ty;
+            }
+        }
+
+        field
+    }
+
+    /// Replaces the type of every default value and constant in `items` with the resolved
+    /// type
+    fn resolve_defaults(&self, items: &mut [Item]) {
+        for item in items.iter_mut() {
+            let fields = match item {
+                Item::Constant(constant) => {
+                    if let Some(ty) = self.check(&constant.typ, &constant.value) {
+                        constant.resolved = ty;
+                    }
+                    continue;
+                }
+                Item::Struct(struct_) => struct_.fields.iter_mut().collect::<Vec<_>>(),
+                Item::Union(union_) => union_
+                    .variants
+                    .iter_mut()
+                    .flat_map(|variant| variant.fields.iter_mut())
+                    .collect(),
+                _ => continue,
+            };
+
+            for field in fields {
+                if let Some(default) = field.default.as_mut() {
+                    if let Some(ty) = self.check(&field.datatype, &default.value) {
+                        default.ty = ty;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Resolves the fields structs inherit from the structs they extend
+struct Inheritance<'a> {
+    structs: HashMap<&'a str, &'a StructDefinition>,
+    /// Inherited fields of the structs resolved so far. A struct maps to None while it is
+    /// being resolved, and stays so if it (indirectly) extends itself
+    resolved: HashMap<String, Option<Vec<StructField>>>,
+}
+
+impl<'a> Inheritance<'a> {
+    fn new(items: &'a [Item]) -> Self {
+        let structs = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(struct_) => Some((struct_.name.as_str(), struct_)),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            structs,
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// The fields the struct `name` inherits, or None if it extends itself. Types that
+    /// can not be extended are reported on their own and give no fields
+    fn inherited(&mut self, name: &str) -> Option<Vec<StructField>> {
+        if let Some(resolved) = self.resolved.get(name) {
+            return resolved.clone();
+        }
+
+        let struct_ = *self.structs.get(name)?;
+
+        let (base_name, args) = match &struct_.base {
+            Some(TyKind::UserDefined(base)) => (base, &[][..]),
+            Some(TyKind::Generic(base, args)) => (base, args.as_slice()),
+            _ => return Some(vec![]),
+        };
+
+        let Some(&base) = self.structs.get(base_name.as_str()) else {
+            return Some(vec![]);
+        };
+
+        self.resolved.insert(name.to_string(), None);
+
+        let mut fields = self.inherited(base_name)?;
+        fields.extend(base.fields.iter().cloned());
+
+        let fields = fields
+            .into_iter()
+            .map(|field| StructField {
+                datatype: field.datatype.substitute(&base.params, args),
+                ..field
             })
+            .collect::<Vec<_>>();
+
+        self.resolved.insert(name.to_string(), Some(fields.clone()));
+
+        Some(fields)
+    }
+
+    /// The inherited fields of every struct that was resolved without errors
+    fn into_resolved(self) -> HashMap<String, Vec<StructField>> {
+        self.resolved
+            .into_iter()
+            .filter_map(|(name, fields)| Some((name, fields?)))
+            .collect()
+    }
+}
+
+/// Can `name` be the key of a field on the wire ? Go writes it in a struct tag
+/// (`json:"name"`), which can not hold every character
+fn is_wire_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '"' | '\'' | '`' | '\\' | ','))
+}
+
+/// Sorts `items` such that every item comes after the items it depends upon, e.g so
+/// that a Python type alias is never emitted before the class it refers to. Other than
+/// that (and for recursive types) the declaration order is preserved
+fn dependency_order(items: Vec<Item>) -> Vec<Item> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+
+    fn visit(
+        index: usize,
+        items: &[Item],
+        indices: &HashMap<&str, usize>,
+        marks: &mut [Mark],
+        order: &mut Vec<usize>,
+    ) {
+        if marks[index] != Mark::Unvisited {
+            return;
+        }
+
+        marks[index] = Mark::InProgress;
+
+        for dep in items[index].dependencies() {
+            // Imported types have no index
+            if let Some(&dep_index) = indices.get(dep) {
+                visit(dep_index, items, indices, marks, order);
+            }
         }
+
+        marks[index] = Mark::Done;
+        order.push(index);
+    }
+
+    let indices = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.is_type())
+        .map(|(i, item)| (item.name(), i))
+        .collect::<HashMap<_, _>>();
+
+    let mut marks = vec![Mark::Unvisited; items.len()];
+    let mut order = Vec::with_capacity(items.len());
+
+    for index in 0..items.len() {
+        visit(index, &items, &indices, &mut marks, &mut order);
     }
+
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+
+    order
+        .into_iter()
+        .filter_map(|index| items[index].take())
+        .collect()
 }
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::items::Program;
+use crate::lexer::{ForserFile, Lexer};
+use crate::parser::{ImportResolver, ParseError, ParseErrorKind, Parser};
+
+#[derive(Error, Debug)]
+pub enum LoadError {
+    #[error("Failed to read \"{path}\": {source}")]
+    Io { path: PathBuf, source: io::Error },
+
+    /// The file was parsed but contains errors. See [`Resolver::failures`]
+    #[error("\"{0}\" contains errors")]
+    Invalid(PathBuf),
+}
+
+/// A file that failed to parse
+pub struct Failure<'a> {
+    pub path: &'a Path,
+    pub source: &'a str,
+    pub errors: &'a [ParseError],
+}
+
+struct Module {
+    source: String,
+    result: Result<Program, Vec<ParseError>>,
+}
+
+/// Loads forser files along with everything they import. Each file is parsed only
+/// once, no matter how many times it is imported
+pub struct Resolver {
+    /// Every file loaded so far, keyed by its canonical path
+    modules: HashMap<PathBuf, Module>,
+    /// Order in which the files were loaded
+    order: Vec<PathBuf>,
+    /// Files currently being parsed, used to detect import cycles
+    stack: Vec<PathBuf>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            modules: HashMap::new(),
+            order: vec![],
+            stack: vec![],
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<Program, LoadError> {
+        let io_error = |source| LoadError::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        };
+
+        let path = path.as_ref().canonicalize().map_err(io_error)?;
+
+        if let Some(module) = self.modules.get(&path) {
+            return module.result.clone().map_err(|_| LoadError::Invalid(path));
+        }
+
+        let file = ForserFile::new(&path).map_err(io_error)?;
+
+        let mut source = file.source();
+        let lexer = Lexer::new(&mut source);
+
+        self.stack.push(path.clone());
+
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut imports = FileImports {
+            resolver: self,
+            base_dir,
+        };
+
+        let result = Parser::with_resolver(lexer, &mut imports).parse();
+
+        self.stack.pop();
+        self.order.push(path.clone());
+        self.modules.insert(
+            path.clone(),
+            Module {
+                source: file.contents().to_string(),
+                result: result.clone(),
+            },
+        );
+
+        result.map_err(|_| LoadError::Invalid(path))
+    }
+
+    /// All the loaded files that contain errors, in the order they were loaded
+    pub fn failures(&self) -> impl Iterator<Item = Failure<'_>> {
+        self.order.iter().filter_map(|path| {
+            let module = &self.modules[path];
+
+            module.result.as_ref().err().map(|errors| Failure {
+                path,
+                source: &module.source,
+                errors,
+            })
+        })
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves the imports of a single file, relative to the directory it lives in
+struct FileImports<'a> {
+    resolver: &'a mut Resolver,
+    base_dir: PathBuf,
+}
+
+impl<'a> ImportResolver for FileImports<'a> {
+    fn resolve(&mut self, path: &str) -> Result<Vec<(String, usize)>, ParseErrorKind> {
+        let full_path = self.base_dir.join(path);
+
+        let canonical = full_path
+            .canonicalize()
+            .map_err(|err| ParseErrorKind::ImportFailed {
+                path: path.to_string(),
+                reason: err.to_string(),
+            })?;
+
+        if let Some(start) = self.resolver.stack.iter().position(|p| *p == canonical) {
+            let cycle = self.resolver.stack[start..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            return Err(ParseErrorKind::ImportCycle(cycle));
+        }
+
+        match self.resolver.load(&canonical) {
+            Ok(program) => Ok(program
+                .type_arities()
+                .map(|(name, arity)| (name.to_string(), arity))
+                .collect()),
+            Err(err) => Err(ParseErrorKind::ImportFailed {
+                path: path.to_string(),
+                reason: match err {
+                    LoadError::Io { source, .. } => source.to_string(),
+                    LoadError::Invalid(_) => "the file contains errors".to_string(),
+                },
+            }),
+        }
+    }
+}
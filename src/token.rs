@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenKind {
     // Keywords
@@ -17,15 +19,23 @@ pub enum TokenKind {
     Comma,
     Colon,
     Semicolon,
+    Dot,
     QuestionMark,
     Equal,
+    Arrow,
+    At,
 
     // Identifiers
     Identifier(String),
 
     // Literals
     StringLiteral(String),
-    IntLiteral(i32),
+    IntLiteral(i128),
+    /// The literal as written, e.g `2.5` or `-1e3`
+    FloatLiteral(String),
+
+    // The text of a `///` comment
+    DocComment(String),
 
     // Unknowm
     Unknowm(char),
@@ -35,6 +45,69 @@ pub enum TokenKind {
     Eof,
 }
 
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let punct = match self {
+            TokenKind::Struct => "`struct`",
+            TokenKind::Enum => "`enum`",
+            TokenKind::Type => "`type`",
+            TokenKind::ParenLeft => "`(`",
+            TokenKind::ParenRight => "`)`",
+            TokenKind::BraceLeft => "`{`",
+            TokenKind::BraceRight => "`}`",
+            TokenKind::SquareLeft => "`[`",
+            TokenKind::SquareRight => "`]`",
+            TokenKind::AngleLeft => "`<`",
+            TokenKind::AngleRight => "`>`",
+            TokenKind::Comma => "`,`",
+            TokenKind::Colon => "`:`",
+            TokenKind::Semicolon => "`;`",
+            TokenKind::Dot => "`.`",
+            TokenKind::QuestionMark => "`?`",
+            TokenKind::Equal => "`=`",
+            TokenKind::Arrow => "`->`",
+            TokenKind::At => "`@`",
+            TokenKind::Init => "start of file",
+            TokenKind::Eof => "end of file",
+
+            // An empty identifier stands for "any" identifier
+            TokenKind::Identifier(ident) if ident.is_empty() => "identifier",
+
+            TokenKind::Identifier(ident) => return write!(f, "identifier `{}`", ident),
+            TokenKind::StringLiteral(val) => return write!(f, "string \"{}\"", val),
+            TokenKind::IntLiteral(val) => return write!(f, "integer `{}`", val),
+            TokenKind::FloatLiteral(val) => return write!(f, "float `{}`", val),
+            TokenKind::DocComment(..) => "doc comment",
+            TokenKind::Unknowm(c) => return write!(f, "unknown character `{}`", c),
+        };
+
+        f.write_str(punct)
+    }
+}
+
+/// A region of the source file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    /// Character offset of the start of the region
+    pub start: i64,
+    /// Character offset of the end (exclusive) of the region
+    pub end: i64,
+    /// Line of the start of the region, 1-based
+    pub line: i64,
+    /// Column of the start of the region, 1-based
+    pub column: i64,
+}
+
+impl Span {
+    /// Returns a span starting at `self` and ending at the end of `other`
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end.max(self.end),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Token {
     pub kind: TokenKind,
@@ -54,6 +127,15 @@ impl Token {
             line: 1,
         }
     }
+
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.pos_start,
+            end: self.pos_end,
+            line: self.line,
+            column: self.column,
+        }
+    }
 }
 
 pub fn to_keyword(ident: &str) -> Option<TokenKind> {
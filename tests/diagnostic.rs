@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use forser::diagnostic::render;
+use forser::parser::{ParseError, ParseErrorKind};
+use forser::token::Span;
+
+fn unknown_type(name: &str, start: i64, end: i64, line: i64, column: i64) -> ParseError {
+    ParseError {
+        kind: ParseErrorKind::UnknownType(name.to_string()),
+        span: Span {
+            start,
+            end,
+            line,
+            column,
+        },
+    }
+}
+
+#[test]
+fn carets_underline_the_span() {
+    let source = "struct User {\n    roles: [Rolee]\n}\n";
+    let error = unknown_type("Rolee", 26, 31, 2, 13);
+
+    assert_eq!(
+        render(Path::new("user.fr"), source, &error),
+        concat!(
+            "error: Unknown Type \"Rolee\"\n",
+            " --> user.fr:2:13\n",
+            "  |\n",
+            "2 |     roles: [Rolee]\n",
+            "  |             ^^^^^\n",
+        )
+    );
+}
+
+/// Only the first line of a span over several lines is shown, underlined up to its end
+#[test]
+fn spans_over_several_lines_stop_at_the_end_of_the_first() {
+    let source = "\n\n\n\n\n\n\n\n\nstruct User {\n    roles: [Role]\n}\n";
+    let error = unknown_type("User", 9, 42, 10, 1);
+
+    assert_eq!(
+        render(Path::new("user.fr"), source, &error),
+        concat!(
+            "error: Unknown Type \"User\"\n",
+            "  --> user.fr:10:1\n",
+            "   |\n",
+            "10 | struct User {\n",
+            "   | ^^^^^^^^^^^^^\n",
+        )
+    );
+}
+
+/// Tabs are kept in front of the carets, so that they line up whatever the tab width
+#[test]
+fn carets_line_up_after_tabs() {
+    let source = "struct User {\n\troles: [Rolee]\n}\n";
+    let error = unknown_type("Rolee", 23, 28, 2, 10);
+
+    assert_eq!(
+        render(Path::new("user.fr"), source, &error),
+        concat!(
+            "error: Unknown Type \"Rolee\"\n",
+            " --> user.fr:2:10\n",
+            "  |\n",
+            "2 | \troles: [Rolee]\n",
+            "  | \t        ^^^^^\n",
+        )
+    );
+}
@@ -0,0 +1,76 @@
+use std::fs;
+
+use forser::generators::{
+    GoGenerator, JsonSchemaGenerator, PythonGenerator, RustGenerator, TypeScriptGenerator,
+};
+use forser::language::{link_imports, Language};
+use forser::resolver::Resolver;
+
+/// Every generator, along with the name of the file its output is compared to
+fn generators() -> Vec<(&'static str, Box<dyn Language>)> {
+    vec![
+        ("ts.ts", Box::new(TypeScriptGenerator::new())),
+        (
+            "ts-decoders.ts",
+            Box::new(TypeScriptGenerator::with_decoders()),
+        ),
+        ("py.py", Box::new(PythonGenerator::new())),
+        ("rs.rs", Box::new(RustGenerator::new())),
+        (
+            "go.go",
+            Box::new(GoGenerator::with_package("golden").unwrap()),
+        ),
+        ("jsonschema.json", Box::new(JsonSchemaGenerator::new())),
+    ]
+}
+
+/// The code generated from every schema in `files/golden` must match the files in the
+/// directory named after the schema, e.g `files/golden/messages/rs.rs`. Running the tests
+/// with `FORSER_BLESS=1` writes the generated code there instead
+#[test]
+fn generated_code_matches_the_expected_output() {
+    let mut paths = fs::read_dir("files/golden")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "fr"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    assert!(!paths.is_empty());
+
+    let bless = std::env::var_os("FORSER_BLESS").is_some();
+    let out_dir = std::env::temp_dir().join(format!("forser-golden-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    for path in paths {
+        let expected_dir = path.with_extension("");
+        let mut program = Resolver::new().load(&path).unwrap();
+
+        for (name, lang) in generators() {
+            link_imports(lang.as_ref(), "[name].[ext]", &mut program);
+
+            let out_file = out_dir.join(name);
+            lang.generate(&program, &out_file);
+            let found = fs::read_to_string(&out_file).unwrap();
+
+            let expected_file = expected_dir.join(name);
+            if bless {
+                fs::create_dir_all(&expected_dir).unwrap();
+                fs::write(&expected_file, &found).unwrap();
+                continue;
+            }
+
+            let expected = fs::read_to_string(&expected_file)
+                .unwrap_or_else(|err| panic!("{}: {}", expected_file.display(), err));
+            assert!(
+                found == expected,
+                "{} differs from the output of {}:\n{}",
+                expected_file.display(),
+                lang.lang_id(),
+                found
+            );
+        }
+    }
+
+    fs::remove_dir_all(out_dir).unwrap();
+}
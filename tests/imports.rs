@@ -0,0 +1,120 @@
+use std::fs;
+use std::path::Path;
+
+use forser::generators::{
+    JsonSchemaGenerator, PythonGenerator, RustGenerator, TypeScriptGenerator,
+};
+use forser::language::{link_imports, output_filename, Language};
+use forser::resolver::{LoadError, Resolver};
+
+/// Messages of every error reported while loading `path`, prefixed with the name of the
+/// file they were found in
+fn load_errors(path: &str) -> Vec<String> {
+    let mut resolver = Resolver::new();
+    assert!(matches!(resolver.load(path), Err(LoadError::Invalid(_))));
+
+    resolver
+        .failures()
+        .flat_map(|failure| {
+            let name = failure.path.file_name().unwrap().to_string_lossy();
+            failure
+                .errors
+                .iter()
+                .map(move |err| format!("{} {}:{} {}", name, err.span.line, err.span.column, err))
+        })
+        .collect()
+}
+
+/// Generates `files/imports/main.fr` with `lang` and returns the generated code
+fn generate_main(lang: &dyn Language) -> String {
+    let schema = Path::new("files/imports/main.fr");
+    let mut program = Resolver::new().load(schema).unwrap();
+    link_imports(lang, "[name].[ext]", &mut program);
+
+    let out_dir = std::env::temp_dir().join(format!("forser-imports-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let out_file = out_dir.join(output_filename(lang, "[name].[ext]", schema));
+    lang.generate(&program, &out_file);
+    fs::read_to_string(out_file).unwrap()
+}
+
+#[test]
+fn imports_name_the_generated_files() {
+    let python = PythonGenerator::new();
+    let rust = RustGenerator::new();
+    let typescript = TypeScriptGenerator::new();
+
+    let schema = Path::new("files/imports/other/my-common.fr");
+    assert_eq!(
+        output_filename(&python, "[name].[ext]", schema),
+        "my_common.py"
+    );
+    assert_eq!(
+        output_filename(&rust, "[name].[ext]", schema),
+        "my_common.rs"
+    );
+    assert_eq!(
+        output_filename(&typescript, "[name].[ext]", schema),
+        "my-common.ts"
+    );
+
+    let code = generate_main(&python);
+    assert!(code.contains("\nfrom my_common import Shared, Level, "));
+    assert!(code.contains("\nfrom plain import Plain, "));
+
+    let code = generate_main(&rust);
+    assert!(code.contains("\nuse super::my_common::{Shared, Level};\n"));
+    assert!(code.contains("\nuse super::plain::{Plain};\n"));
+
+    let code = generate_main(&typescript);
+    assert!(code.contains("\nimport { Shared, Level } from \"./my-common\";\n"));
+    assert!(code.contains("\nimport { Plain } from \"./plain\";\n"));
+
+    let code = generate_main(&JsonSchemaGenerator::new());
+    assert!(code.contains("\"my-common.json#/$defs/Shared\""));
+}
+
+#[test]
+fn import_cycles_are_reported() {
+    let path = fs::canonicalize("files/imports").unwrap();
+    let a = path.join("cycle-a.fr");
+    let b = path.join("cycle-b.fr");
+
+    assert_eq!(
+        load_errors("files/imports/cycle-a.fr"),
+        [
+            format!(
+                "cycle-b.fr 1:8 Import cycle detected: {} -> {} -> {}",
+                a.display(),
+                b.display(),
+                a.display()
+            ),
+            "cycle-a.fr 1:8 Failed to import \"cycle-b.fr\": the file contains errors".to_string(),
+        ]
+    );
+}
+
+/// The types a failed import would have brought in scope are unknown, but only the
+/// import itself is reported
+#[test]
+fn failed_imports_are_reported_once() {
+    assert_eq!(
+        load_errors("files/imports/missing.fr"),
+        [
+            "missing.fr 1:22 Failed to import \"nowhere.fr\": No such file or directory (os error 2)",
+            "missing.fr 2:8 Failed to import \"nowhere-either.fr\": No such file or directory (os error 2)",
+        ]
+    );
+}
+
+#[test]
+fn imported_generics_take_their_type_arguments() {
+    assert_eq!(
+        load_errors("files/imports/arity.fr"),
+        [
+            "arity.fr 5:8 Type \"P\" takes 1 type argument(s), found 0",
+            "arity.fr 6:8 Type \"R\" takes 0 type argument(s), found 1",
+        ]
+    );
+}
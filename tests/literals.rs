@@ -0,0 +1,41 @@
+use forser::items::{Item, Value};
+use forser::lexer::{ForserFile, Lexer};
+use forser::parser::Parser;
+
+/// Integer literals at the bounds of the 64 bit types keep their exact value, the
+/// ones past them are rejected in `files/broken/bounds.fr`
+#[test]
+fn boundary_literals_keep_their_value() {
+    let file = ForserFile::new("files/golden/primitives.fr").unwrap();
+    let mut source = file.source();
+    let program = Parser::new(Lexer::new(&mut source)).parse().unwrap();
+
+    let constants = program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Constant(constant) => Some((constant.name.as_str(), constant.value.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        constants,
+        [
+            ("INT_MIN", Value::Int(i32::MIN as i128)),
+            ("INT_MAX", Value::Int(i32::MAX as i128)),
+            ("I64_MIN", Value::Int(i64::MIN as i128)),
+            ("I64_MAX", Value::Int(i64::MAX as i128)),
+            ("U64_MAX", Value::Int(u64::MAX as i128)),
+            ("U64_MIN", Value::Int(0)),
+        ]
+    );
+
+    let Some(Item::Struct(numbers)) = program.items.first() else {
+        panic!("Expected the struct Numbers first");
+    };
+
+    let single = numbers.fields.iter().find(|f| f.name == "single").unwrap();
+    let value = &single.default.as_ref().unwrap().value;
+    assert!(matches!(value, Value::Float(val) if *val as f32 == f32::MAX));
+}
@@ -0,0 +1,29 @@
+use forser::items::Item;
+use forser::lexer::{ForserFile, Lexer};
+use forser::parser::Parser;
+
+/// Items come after the items they depend upon, through fields, base structs, aliases,
+/// variants and constants. Otherwise they are kept in declaration order
+#[test]
+fn items_follow_their_dependencies() {
+    let file = ForserFile::new("files/order.fr").unwrap();
+    let mut source = file.source();
+    let program = Parser::new(Lexer::new(&mut source)).parse().unwrap();
+
+    let names = program.items.iter().map(Item::name).collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        [
+            "Base",
+            "Tree",
+            "Node",
+            "Member",
+            "Level",
+            "Admin",
+            "Admins",
+            "DEFAULT_LEVEL",
+            "Event",
+        ]
+    );
+}
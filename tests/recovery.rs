@@ -0,0 +1,41 @@
+use std::fs;
+
+use forser::lexer::{ForserFile, Lexer};
+use forser::parser::Parser;
+
+/// Every schema in `files/broken` lists the errors it must produce, in order, as
+/// `// expect: <line>:<column> <message>` comments. Anything else reported is noise
+#[test]
+fn broken_schemas_report_every_error_once() {
+    let mut paths = fs::read_dir("files/broken")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        let file = ForserFile::new(&path).unwrap();
+
+        let expected = file
+            .contents()
+            .lines()
+            .filter_map(|line| line.strip_prefix("// expect: "))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        let mut source = file.source();
+        let errors = Parser::new(Lexer::new(&mut source))
+            .parse()
+            .err()
+            .unwrap_or_default();
+
+        let found = errors
+            .iter()
+            .map(|err| format!("{}:{} {}", err.span.line, err.span.column, err))
+            .collect::<Vec<_>>();
+
+        assert_eq!(found, expected, "errors of {}", path.display());
+    }
+}
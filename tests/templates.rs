@@ -0,0 +1,45 @@
+use std::fs;
+
+use forser::glang::{compile_template, render_template};
+use forser::resolver::Resolver;
+
+/// `files/templates/blocks.gx` renders `blocks.fr` to `blocks.md`, going through both
+/// branches of `%if%` blocks, inline and on lines of their own, and nested `%for%` loops
+#[test]
+fn blocks_render_as_expected() {
+    let template = fs::read_to_string("files/templates/blocks.gx").unwrap();
+    let program = Resolver::new().load("files/templates/blocks.fr").unwrap();
+
+    let mut found = vec![];
+    render_template(&template, &program, None, &mut found).unwrap();
+
+    let expected = fs::read_to_string("files/templates/blocks.md").unwrap();
+    assert_eq!(String::from_utf8(found).unwrap(), expected);
+}
+
+/// Every template in `files/templates/broken` names the error it must be rejected with
+/// in a `// expect: <line>:<column>: <message>` line, written outside of any section
+#[test]
+fn broken_templates_are_rejected() {
+    let mut paths = fs::read_dir("files/templates/broken")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+
+        let expected = source
+            .lines()
+            .find_map(|line| line.strip_prefix("// expect: "))
+            .unwrap_or_else(|| panic!("{} expects no error", path.display()));
+
+        match compile_template(&source) {
+            Ok(_) => panic!("{} is accepted", path.display()),
+            Err(err) => assert_eq!(err.to_string(), expected, "error of {}", path.display()),
+        }
+    }
+}